@@ -0,0 +1,746 @@
+//! The alignment algorithm that is shared by all public functions.
+
+use crate::html::{HtmlEntry, HtmlFormat};
+use crate::number::BufferedNumber;
+#[cfg(feature = "alloc")]
+use crate::number::FormattedNumbers;
+use crate::options::{
+    AlignOptions, OutputStyle, Overflow, SignMode, FIGURE_SPACE, MINUS_SIGN, PUNCTUATION_SPACE,
+};
+use crate::parts::FractionParts;
+use crate::width::display_width as width;
+use crate::{AlignableNumber, FormatPrecision, Notation};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::convert::Infallible;
+use core::fmt;
+
+/// The column widths of a list of aligned numbers together with the [`AlignOptions`].
+///
+/// This is the first pass of a two-pass API: The layout is computed once from all
+/// entries. Afterwards, every entry is rendered with [`Self::display`] into any
+/// [`fmt::Write`] or [`fmt::Formatter`] without allocating a `String` per entry.
+///
+/// A layout can also be computed from a sample or from declared bounds with
+/// [`Self::from_bounds`] and reused for several batches with [`Self::align`], e.g. for
+/// pages or log chunks, so that all batches are aligned identically. Entries that
+/// don't fit are rendered according to [`AlignOptions::overflow`].
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{AlignOptions, AlignmentLayout};
+///
+/// let input = ["-42", "0.3214", "1000"];
+/// let layout = AlignmentLayout::new(&input, &AlignOptions::new());
+/// assert_eq!((4, 5), (layout.whole_width(), layout.fraction_width()));
+///
+/// let mut output = String::new();
+/// for entry in layout.display_all(&input) {
+///     output += &format!("[{}]", entry);
+/// }
+/// assert_eq!(output, "[ -42     ][   0.3214][1000     ]");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AlignmentLayout {
+    /// Width of the widest sign and whole part.
+    whole_width: usize,
+    /// Width of the widest fractional part including the decimal separator.
+    fraction_width: usize,
+    /// Width of the widest suffix, i.e. exponent including the `e` or SI prefix.
+    suffix_width: usize,
+    options: AlignOptions,
+}
+
+impl AlignmentLayout {
+    /// Computes the layout of a list of formatted fraction number strings, such as the
+    /// input of [`crate::fmt_align_fraction_strings_with`].
+    pub fn new<S: AsRef<str>>(strings: &[S], options: &AlignOptions) -> Self {
+        let decimal_separator = options.input_locale.decimal_separator;
+        Self::from_parts(
+            strings
+                .iter()
+                .map(|s| FractionParts::parse(s.as_ref(), decimal_separator)),
+            options,
+        )
+    }
+
+    /// Computes the layout of a list of numbers, such as the input of
+    /// [`crate::fmt_align_numbers_with`]. With [`FormatPrecision::Exact`], unnecessary
+    /// zeroes are kept. The numbers are rendered with [`Self::display_number`].
+    ///
+    /// Like in [`crate::fmt_align_numbers_with`], a number whose [`AlignableNumber`]
+    /// implementation fails is measured as an empty entry.
+    #[cfg(feature = "alloc")]
+    pub fn from_numbers<T: AlignableNumber>(
+        numbers: &[T],
+        precision: FormatPrecision,
+        options: &AlignOptions,
+    ) -> Self {
+        let layout = Self::measure_numbers(numbers, precision, options, |formatted| {
+            Ok::<_, Infallible>(formatted.unwrap_or_default())
+        });
+        match layout {
+            Ok(layout) => layout,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Like [`Self::from_numbers`] but fails if the [`AlignableNumber`] implementation
+    /// of a number fails.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn try_from_numbers<T: AlignableNumber>(
+        numbers: &[T],
+        precision: FormatPrecision,
+        options: &AlignOptions,
+    ) -> Result<Self, fmt::Error> {
+        Self::measure_numbers(numbers, precision, options, |formatted| formatted)
+    }
+
+    /// Measures the numbers, which are passed through `handle` after formatting.
+    #[cfg(feature = "alloc")]
+    fn measure_numbers<T: AlignableNumber, E>(
+        numbers: &[T],
+        precision: FormatPrecision,
+        options: &AlignOptions,
+        handle: impl Fn(Result<BufferedNumber, fmt::Error>) -> Result<BufferedNumber, E>,
+    ) -> Result<Self, E> {
+        let options = options.for_precision(precision);
+        // the numbers are formatted on the stack and measured one by one, so that they
+        // are not stored; with SI prefixes, they are formatted once more to find out
+        // whether any has a prefix
+        let format = |number, reserve_prefix| {
+            handle(BufferedNumber::new(
+                number,
+                precision,
+                &options,
+                reserve_prefix,
+            ))
+        };
+        let mut reserve_prefix = false;
+        if let Notation::SiPrefix { .. } = options.notation {
+            for number in numbers {
+                if format(number, false)?.has_prefix() {
+                    reserve_prefix = true;
+                    break;
+                }
+            }
+        }
+        numbers
+            .iter()
+            .try_fold(Self::empty(&options), |layout, number| {
+                Ok(layout.with_parts(format(number, reserve_prefix)?.parts()))
+            })
+    }
+
+    /// Computes the layout from declared bounds instead of from the entries: whole
+    /// parts with up to `whole_digits` digits, up to `fraction_digits` fractional
+    /// digits and, if `negative` is set, a column for the minus sign. Grouping
+    /// separators of the output and [`SignMode::Always`] are taken into account.
+    ///
+    /// The suffix is derived from the [`Notation`]: an exponent with a sign and up to
+    /// two digits, such as `e-12`, or a space, an SI prefix and the unit, such as ` kΩ`.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// use fraction_list_fmt_align::{AlignOptions, AlignmentLayout, Overflow};
+    ///
+    /// let options = AlignOptions::new().overflow(Overflow::Mark);
+    /// let layout = AlignmentLayout::from_bounds(3, 2, true, &options);
+    /// assert_eq!(layout.align(&["1.5", "-20"]), ["   1.5 ", " -20   "]);
+    /// assert_eq!(layout.align(&["999.99", "-1000"]), [" 999.99", "#######"]);
+    /// # }
+    /// ```
+    pub fn from_bounds(
+        whole_digits: usize,
+        fraction_digits: usize,
+        negative: bool,
+        options: &AlignOptions,
+    ) -> Self {
+        let sign_width = usize::from(negative || options.sign_mode == SignMode::Always);
+        let separator_width = match options.output_locale.grouping_separator() {
+            Some(_) => options.output_locale.grouping.separator_count(whole_digits),
+            None => 0,
+        };
+        let suffix_width = match options.notation {
+            Notation::Fixed => 0,
+            Notation::Scientific | Notation::Auto | Notation::Engineering => 4,
+            // the prefix column is always reserved, see Self::reserves_prefix
+            Notation::SiPrefix { unit } => 2 + width(unit),
+        };
+        Self {
+            whole_width: sign_width + whole_digits + separator_width,
+            fraction_width: if fraction_digits > 0 {
+                fraction_digits + 1
+            } else {
+                0
+            },
+            suffix_width,
+            options: *options,
+        }
+    }
+
+    pub(crate) fn from_parts<'a>(
+        parts: impl IntoIterator<Item = FractionParts<'a>>,
+        options: &AlignOptions,
+    ) -> Self {
+        parts
+            .into_iter()
+            .fold(Self::empty(options), |layout, p| layout.with_parts(p))
+    }
+
+    /// Returns the layout of an empty list.
+    pub(crate) const fn empty(options: &AlignOptions) -> Self {
+        Self {
+            whole_width: 0,
+            fraction_width: 0,
+            suffix_width: 0,
+            options: *options,
+        }
+    }
+
+    /// Returns the layout that additionally fits the given parts.
+    pub(crate) fn with_parts(self, parts: FractionParts) -> Self {
+        let options = &self.options;
+        let p = self.prepare(parts);
+        Self {
+            whole_width: self
+                .whole_width
+                .max(width(rendered_sign(&p, options)) + whole_part_width(&p, options)),
+            fraction_width: self
+                .fraction_width
+                .max(p.fraction.map_or(0, |f| 1 + width(f))),
+            suffix_width: self.suffix_width.max(p.suffix.map_or(0, width)),
+            ..self
+        }
+    }
+
+    /// Returns the width of the widest sign and whole part, including grouping
+    /// separators.
+    pub const fn whole_width(&self) -> usize {
+        self.whole_width
+    }
+
+    /// Returns the width of the widest fractional part including the decimal separator.
+    /// Zero if no entry has a fractional part.
+    pub const fn fraction_width(&self) -> usize {
+        self.fraction_width
+    }
+
+    /// Returns the width of the widest suffix, i.e. exponent or SI prefix and unit.
+    pub const fn suffix_width(&self) -> usize {
+        self.suffix_width
+    }
+
+    /// Returns the display width of every aligned entry, including the padding to
+    /// reach [`AlignOptions::min_width`]. With `right_pad(false)`, entries may be
+    /// narrower.
+    pub fn width(&self) -> usize {
+        self.content_width().max(self.options.min_width)
+    }
+
+    /// Returns the [`AlignOptions`] of the layout.
+    pub const fn options(&self) -> &AlignOptions {
+        &self.options
+    }
+
+    /// Returns the aligned rendering of a formatted fraction number string, which
+    /// implements [`fmt::Display`]. Entries that don't fit into the layout are rendered
+    /// according to [`AlignOptions::overflow`]; with [`Overflow::Widen`], they break the
+    /// alignment.
+    pub fn display<'a>(&'a self, string: &'a str) -> AlignedEntry<'a> {
+        let decimal_separator = self.options.input_locale.decimal_separator;
+        self.display_parts(FractionParts::parse(string, decimal_separator))
+    }
+
+    /// Returns the aligned renderings of all strings, see [`Self::display`].
+    pub fn display_all<'a, S: AsRef<str>>(
+        &'a self,
+        strings: &'a [S],
+    ) -> impl Iterator<Item = AlignedEntry<'a>> + 'a {
+        strings.iter().map(move |s| self.display(s.as_ref()))
+    }
+
+    /// Returns the aligned rendering of a number, which implements [`fmt::Display`]. The
+    /// number is formatted with the precision and the options of the layout into a
+    /// buffer on the stack when the entry is displayed, so that no `String` is allocated
+    /// per number. With [`FormatPrecision::Exact`], unnecessary zeroes are kept.
+    ///
+    /// Displaying the entry fails with [`fmt::Error`] if the [`AlignableNumber`]
+    /// implementation fails. Without the `alloc` feature, only [`Notation::Fixed`] is
+    /// supported and the formatted number must not be longer than 128 bytes; otherwise,
+    /// displaying the entry fails as well.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// use fraction_list_fmt_align::{AlignOptions, AlignmentLayout, FormatPrecision};
+    ///
+    /// let numbers = [-42.0, 0.3214, 1000.0];
+    /// let precision = FormatPrecision::Max(4);
+    /// let layout = AlignmentLayout::from_numbers(&numbers, precision, &AlignOptions::new());
+    ///
+    /// let mut output = String::new();
+    /// for number in &numbers {
+    ///     output += &format!("[{}]", layout.display_number(number, precision));
+    /// }
+    /// assert_eq!(output, "[ -42     ][   0.3214][1000     ]");
+    /// # }
+    /// ```
+    pub fn display_number<'a, T: AlignableNumber + ?Sized>(
+        &self,
+        number: &'a T,
+        precision: FormatPrecision,
+    ) -> NumberEntry<'a, T> {
+        NumberEntry {
+            layout: Self {
+                options: self.options.for_precision(precision),
+                ..*self
+            },
+            number,
+            precision,
+        }
+    }
+
+    /// Returns the aligned rendering of a formatted fraction number string as HTML,
+    /// which implements [`fmt::Display`]. See [`HtmlFormat`] for the markup.
+    pub fn display_html<'a>(&self, string: &'a str, format: HtmlFormat<'a>) -> HtmlEntry<'a> {
+        let decimal_separator = self.options.input_locale.decimal_separator;
+        HtmlEntry::new(
+            *self,
+            FractionParts::parse(string, decimal_separator),
+            format,
+        )
+    }
+
+    /// Aligns a batch of formatted fraction number strings with this layout, like
+    /// [`crate::fmt_align_fraction_strings_with`] with the options of the layout.
+    /// Entries that don't fit are rendered according to [`AlignOptions::overflow`].
+    #[cfg(feature = "alloc")]
+    pub fn align<S: AsRef<str>>(&self, strings: &[S]) -> Vec<String> {
+        let decimal_separator = self.options.input_locale.decimal_separator;
+        let parts = strings
+            .iter()
+            .map(|s| FractionParts::parse(s.as_ref(), decimal_separator))
+            .collect::<Vec<_>>();
+        self.align_batch(&parts)
+    }
+
+    /// Formats a batch of numbers and aligns them with this layout, like
+    /// [`crate::fmt_align_numbers_with`] with the options of the layout. Entries that
+    /// don't fit are rendered according to [`AlignOptions::overflow`].
+    #[cfg(feature = "alloc")]
+    pub fn align_numbers<T: AlignableNumber>(
+        &self,
+        numbers: &[T],
+        precision: FormatPrecision,
+    ) -> Vec<String> {
+        let formatted = FormattedNumbers::new(numbers, precision, &self.options);
+        let layout = Self {
+            options: *formatted.options(),
+            ..*self
+        };
+        layout.align_batch(&formatted.parts().collect::<Vec<_>>())
+    }
+
+    /// Renders a batch of entries, see [`Self::align`].
+    #[cfg(feature = "alloc")]
+    fn align_batch(&self, parts: &[FractionParts]) -> Vec<String> {
+        let layout = match self.options.overflow {
+            Overflow::Widen => parts.iter().fold(*self, |layout, p| layout.with_parts(*p)),
+            Overflow::Truncate | Overflow::Mark => *self,
+        };
+        layout.render(parts)
+    }
+
+    /// Renders every entry into a `String`.
+    #[cfg(feature = "alloc")]
+    fn render(&self, parts: &[FractionParts]) -> Vec<String> {
+        parts
+            .iter()
+            .map(|p| write_to_string(|string| self.write_parts(string, *p)))
+            .collect()
+    }
+
+    pub(crate) const fn display_parts<'a>(&'a self, parts: FractionParts<'a>) -> AlignedEntry<'a> {
+        AlignedEntry {
+            layout: self,
+            parts,
+        }
+    }
+
+    /// Returns the layout with the given [`OutputStyle`], which doesn't change the
+    /// widths.
+    pub(crate) const fn with_output_style(mut self, output_style: OutputStyle) -> Self {
+        self.options.output_style = output_style;
+        self
+    }
+
+    /// Whether a missing SI prefix is replaced by padding, i.e. whether the suffix column
+    /// is wider than a space and the unit because a number has a prefix.
+    fn reserves_prefix(&self) -> bool {
+        match self.options.notation {
+            Notation::SiPrefix { unit } => self.suffix_width > 1 + width(unit),
+            _ => false,
+        }
+    }
+
+    const fn content_width(&self) -> usize {
+        self.whole_width + self.fraction_width + self.suffix_width
+    }
+
+    /// Fits the parts into the layout according to [`AlignOptions::overflow`]. Returns
+    /// `None` if the entry must be marked.
+    fn fit<'a>(&self, parts: FractionParts<'a>) -> Option<FractionParts<'a>> {
+        let options = &self.options;
+        if options.overflow == Overflow::Widen {
+            return Some(parts);
+        }
+        let whole_width = width(rendered_sign(&parts, options)) + whole_part_width(&parts, options);
+        let fraction_width = parts.fraction.map_or(0, |f| 1 + width(f));
+        let suffix_width = parts.suffix.map_or(0, width);
+        if whole_width > self.whole_width || suffix_width > self.suffix_width {
+            return None;
+        }
+        if fraction_width <= self.fraction_width {
+            return Some(parts);
+        }
+        match options.overflow {
+            // zeroes that were in the middle of the fraction may now be trailing
+            Overflow::Truncate => Some(self.prepare(FractionParts {
+                fraction: parts.fraction.filter(|_| self.fraction_width > 0).map(|f| {
+                    let end = f
+                        .char_indices()
+                        .nth(self.fraction_width - 1)
+                        .map_or(f.len(), |(index, _)| index);
+                    &f[..end]
+                }),
+                ..parts
+            })),
+            _ => None,
+        }
+    }
+
+    /// Applies the options that change the parts before they are measured.
+    fn prepare<'a>(&self, parts: FractionParts<'a>) -> FractionParts<'a> {
+        if self.options.strip_trailing_zeroes {
+            parts.strip_trailing_zeroes()
+        } else {
+            parts
+        }
+    }
+
+    /// Writes the aligned parts of one entry.
+    pub(crate) fn write_parts<W: fmt::Write + ?Sized>(
+        &self,
+        out: &mut W,
+        parts: FractionParts,
+    ) -> fmt::Result {
+        self.write_segments(out, parts, |_, _| Ok(()))
+    }
+
+    /// Writes the aligned parts of one entry and calls `start` in front of every
+    /// [`Segment`]. Segments may be empty.
+    pub(crate) fn write_segments<W: fmt::Write + ?Sized>(
+        &self,
+        out: &mut W,
+        parts: FractionParts,
+        mut start: impl FnMut(&mut W, Segment) -> fmt::Result,
+    ) -> fmt::Result {
+        let options = &self.options;
+        let p = match self.fit(self.prepare(parts)) {
+            Some(p) => p,
+            None => {
+                start(out, Segment::Whole)?;
+                return write_repeated(out, '#', self.width());
+            }
+        };
+        // additional padding on the left to reach the minimum width
+        let indent = options.min_width.saturating_sub(self.content_width());
+
+        start(out, Segment::Whole)?;
+        let sign = rendered_sign(&p, options);
+        let whole_width = width(sign) + whole_part_width(&p, options);
+        write_whole_padding(out, options, whole_width, indent + self.whole_width)?;
+        write_with_minus_signs(out, sign, options)?;
+        write_whole_part(out, &p, options)?;
+
+        // now add padding in the end so that all are exactly same aligned, on left
+        // as well as right; technically this is not really needed, but it may
+        // help in some situations. Also this can be easily revoked with a right trim.
+        // Exponents are always aligned on the "e", SI prefixes and units on their
+        // first character.
+        let pad_fraction = options.right_pad || p.suffix.is_some();
+        let zero_pad = options.zero_pad_fraction && self.fraction_width > 0 && p.is_finite();
+
+        start(out, Segment::Point)?;
+        let mut fraction_width = 0;
+        if p.fraction.is_some() || zero_pad {
+            out.write_char(options.output_locale.decimal_separator)?;
+            fraction_width = 1;
+        } else if pad_fraction && self.fraction_width > 0 {
+            // in typographic mode, the missing decimal separator is replaced by a
+            // punctuation space
+            out.write_char(match options.output_style {
+                OutputStyle::Plain => options.padding_char,
+                OutputStyle::Typographic => PUNCTUATION_SPACE,
+            })?;
+            fraction_width = 1;
+        }
+
+        start(out, Segment::Fraction)?;
+        if let Some(fraction) = p.fraction {
+            out.write_str(fraction)?;
+            fraction_width += width(fraction);
+        }
+        if zero_pad {
+            write_repeated(out, '0', self.fraction_width.saturating_sub(fraction_width))?;
+            fraction_width = self.fraction_width;
+        }
+        if pad_fraction {
+            write_repeated(
+                out,
+                options.effective_padding_char(),
+                self.fraction_width.saturating_sub(fraction_width),
+            )?;
+        }
+
+        start(out, Segment::Suffix)?;
+        let suffix_width = match p.suffix {
+            Some(suffix) => {
+                write_with_minus_signs(out, suffix, options)?;
+                width(suffix)
+            }
+            None => 0,
+        };
+        if options.right_pad {
+            write_repeated(
+                out,
+                options.effective_padding_char(),
+                self.suffix_width.saturating_sub(suffix_width),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The parts of an aligned entry in the order in which they are written by
+/// [`AlignmentLayout::write_segments`]. The padding belongs to the part that it
+/// aligns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Segment {
+    /// The padding on the left, the sign and the whole part.
+    Whole,
+    /// The decimal separator or the padding in its place.
+    Point,
+    /// The fractional part and its padding.
+    Fraction,
+    /// The exponent or SI prefix and unit and their padding.
+    Suffix,
+}
+
+/// One aligned entry of an [`AlignmentLayout`]. The entry is rendered when it is
+/// formatted, e.g. with `write!` or `format!`, without intermediate allocations.
+#[derive(Debug, Copy, Clone)]
+pub struct AlignedEntry<'a> {
+    layout: &'a AlignmentLayout,
+    parts: FractionParts<'a>,
+}
+
+impl fmt::Display for AlignedEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.layout.write_parts(f, self.parts)
+    }
+}
+
+/// One aligned number of an [`AlignmentLayout`], see
+/// [`AlignmentLayout::display_number`]. The number is formatted and rendered when the
+/// entry is formatted, e.g. with `write!` or `format!`.
+#[derive(Debug)]
+pub struct NumberEntry<'a, T: ?Sized> {
+    layout: AlignmentLayout,
+    number: &'a T,
+    precision: FormatPrecision,
+}
+
+impl<T: ?Sized> Clone for NumberEntry<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for NumberEntry<'_, T> {}
+
+impl<T: AlignableNumber + ?Sized> fmt::Display for NumberEntry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let layout = &self.layout;
+        let formatted = BufferedNumber::new(
+            self.number,
+            self.precision,
+            &layout.options,
+            layout.reserves_prefix(),
+        )?;
+        layout.write_parts(f, formatted.parts())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Returns the `String` that `write` writes into. Writing into a `String` never fails,
+/// so the result of `write` is ignored.
+pub(crate) fn write_to_string(write: impl FnOnce(&mut String) -> fmt::Result) -> String {
+    let mut string = String::new();
+    let _ = write(&mut string);
+    string
+}
+
+#[cfg(feature = "alloc")]
+/// Aligns all parts according to the options. This is the common implementation
+/// of all alignment functions of this crate.
+pub(crate) fn align_parts(parts: &[FractionParts], options: &AlignOptions) -> Vec<String> {
+    AlignmentLayout::from_parts(parts.iter().copied(), options).render(parts)
+}
+
+/// Returns the digits of the whole part without grouping separators of the input.
+/// Returns `None` if the whole part consists of more than digits, e.g. `NaN`.
+fn whole_part_digits<'a>(
+    parts: &FractionParts<'a>,
+    options: &AlignOptions,
+) -> Option<impl Iterator<Item = char> + Clone + 'a> {
+    let separator = options.input_locale.grouping_separator();
+    if !parts
+        .whole
+        .chars()
+        .all(|c| c.is_ascii_digit() || Some(c) == separator)
+    {
+        return None;
+    }
+    Some(parts.whole.chars().filter(char::is_ascii_digit))
+}
+
+/// Returns the width of the whole part without the sign, including grouping
+/// separators of the output.
+fn whole_part_width(parts: &FractionParts, options: &AlignOptions) -> usize {
+    whole_part_digits(parts, options).map_or_else(
+        || width(parts.whole),
+        |digits| {
+            let digits = digits.count();
+            match options.output_locale.grouping_separator() {
+                Some(_) => digits + options.output_locale.grouping.separator_count(digits),
+                None => digits,
+            }
+        },
+    )
+}
+
+/// Writes the whole part without the sign, including grouping separators of the
+/// output.
+fn write_whole_part<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    parts: &FractionParts,
+    options: &AlignOptions,
+) -> fmt::Result {
+    match whole_part_digits(parts, options) {
+        Some(digits) => {
+            let count = digits.clone().count();
+            for (index, digit) in digits.enumerate() {
+                if let Some(separator) = options.output_locale.grouping_separator() {
+                    let grouping = options.output_locale.grouping;
+                    if index > 0 && grouping.is_separator_position(count - index) {
+                        out.write_char(separator)?;
+                    }
+                }
+                out.write_char(digit)?;
+            }
+            Ok(())
+        }
+        None => out.write_str(parts.whole),
+    }
+}
+
+/// Writes the padding on the left of the whole part, i.e. the columns right of
+/// `from` up to and including `to`, counted from the decimal separator to the left.
+/// In typographic mode, columns of grouping separators get a punctuation space.
+fn write_whole_padding<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    options: &AlignOptions,
+    from: usize,
+    to: usize,
+) -> fmt::Result {
+    match (
+        options.output_style,
+        options.output_locale.grouping_separator(),
+    ) {
+        (OutputStyle::Typographic, Some(_)) => {
+            for column in (from + 1..=to).rev() {
+                if is_separator_column(options, column) {
+                    out.write_char(PUNCTUATION_SPACE)?;
+                } else {
+                    out.write_char(FIGURE_SPACE)?;
+                }
+            }
+            Ok(())
+        }
+        _ => write_repeated(
+            out,
+            options.effective_padding_char(),
+            to.saturating_sub(from),
+        ),
+    }
+}
+
+/// Whether the column of the whole part, counted from the decimal separator to the
+/// left and starting at one, holds a grouping separator if the whole part is wide
+/// enough.
+fn is_separator_column(options: &AlignOptions, column: usize) -> bool {
+    let grouping = options.output_locale.grouping;
+    let mut digits = 0;
+    let mut current_column = 0;
+    loop {
+        if digits > 0 && grouping.is_separator_position(digits) {
+            current_column += 1;
+            if current_column == column {
+                return true;
+            }
+        }
+        current_column += 1;
+        digits += 1;
+        if current_column >= column {
+            return false;
+        }
+    }
+}
+
+/// Writes a sign or suffix. In typographic mode, the hyphen-minus of a negative sign
+/// or exponent is replaced by the minus sign.
+fn write_with_minus_signs<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    suffix: &str,
+    options: &AlignOptions,
+) -> fmt::Result {
+    match options.output_style {
+        OutputStyle::Plain => out.write_str(suffix),
+        OutputStyle::Typographic => suffix
+            .chars()
+            .try_for_each(|c| out.write_char(if c == '-' { MINUS_SIGN } else { c })),
+    }
+}
+
+/// Returns the sign that is printed for the given parts. In typographic mode, `-` is
+/// written as [`MINUS_SIGN`], which has the same width.
+fn rendered_sign<'a>(parts: &FractionParts<'a>, options: &AlignOptions) -> &'a str {
+    match options.sign_mode {
+        SignMode::Keep => parts.sign,
+        SignMode::Always if parts.sign.is_empty() && parts.whole != "NaN" => "+",
+        SignMode::Always => parts.sign,
+        SignMode::NegativeOnly if parts.sign == "+" => "",
+        SignMode::NegativeOnly => parts.sign,
+    }
+}
+
+fn write_repeated<W: fmt::Write + ?Sized>(out: &mut W, char: char, count: usize) -> fmt::Result {
+    for _ in 0..count {
+        out.write_char(char)?;
+    }
+    Ok(())
+}
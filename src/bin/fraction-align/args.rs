@@ -0,0 +1,265 @@
+//! Parsing of the command line arguments.
+
+use crate::fields::FieldSplit;
+use crate::regex::Regex;
+use fraction_list_fmt_align::{AlignOptions, FormatPrecision, Grouping};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Aligns numbers on their decimal separator, one number per line.
+
+USAGE:
+    fraction-align [OPTIONS] [FILE]...
+
+Reads from stdin if no FILE is given or FILE is `-`. Blank lines are kept.
+
+In field mode, the lines are split into fields and the first number of every field
+is aligned with the numbers of the same field in all other lines, e.g.
+`latency=12.5ms`. The text around the numbers is kept.
+
+OPTIONS:
+    -p, --precision <N>            Reformat the numbers with at most N decimal places
+    -e, --exact                    Reformat with exactly N decimal places, requires -p
+    -d, --decimal-separator <C>    Decimal separator of input and output [default: .]
+    -g, --grouping-separator <C>   Grouping separator of input and output, e.g. ,
+        --grouping <G>             Grouping: a group size, `indian` or `myriad` [default: 3]
+        --padding <C>              Padding character [default: ' ']
+        --no-right-pad             Don't pad on the right
+    -f, --fields                   Field mode with fields separated by whitespace
+    -F, --delimiter <S>            Field mode with fields separated by S
+    -r, --regex <RE>               Field mode with fields separated by matches of RE,
+                                   e.g. `[,;]\\s*`. Supports only `.`, `[...]`, `\\d`,
+                                   `\\s`, `\\w`, `\\t`, `*`, `+` and `?`
+    -o, --output-separator <S>     Separator of the aligned fields [default: the
+                                   delimiter, or ' ' for whitespace and RE]
+    -h, --help                     Print this help
+    -V, --version                  Print the version";
+
+/// What the program does.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Align the numbers of the input.
+    Align(Args),
+    Help,
+    Version,
+}
+
+/// The arguments of [`Command::Align`].
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    /// Precision for reformatting the numbers, `None` to align them as they are.
+    pub precision: Option<FormatPrecision>,
+    pub decimal_separator: Option<char>,
+    pub grouping_separator: Option<char>,
+    pub grouping: Option<Grouping>,
+    pub padding_char: Option<char>,
+    pub right_pad: bool,
+    /// How lines are split into fields in field mode, `None` for one number per line.
+    pub fields: Option<FieldSplit>,
+    pub output_separator: Option<String>,
+    /// Input files; empty or `-` for stdin.
+    pub files: Vec<PathBuf>,
+}
+
+impl Args {
+    /// Returns the [`AlignOptions`] that correspond to the arguments.
+    pub fn options(&self) -> AlignOptions {
+        let mut options = AlignOptions::new()
+            .right_pad(self.right_pad)
+            .grouping_separator(self.grouping_separator);
+        if let Some(decimal_separator) = self.decimal_separator {
+            options = options.decimal_separator(decimal_separator);
+        }
+        if let Some(grouping) = self.grouping {
+            options = options.grouping(grouping);
+        }
+        if let Some(padding_char) = self.padding_char {
+            options = options.padding_char(padding_char);
+        }
+        options
+    }
+}
+
+/// Parses the arguments without the program name. Returns a message for the user
+/// if they are invalid.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut result = Args {
+        right_pad: true,
+        ..Args::default()
+    };
+    let mut precision = None;
+    let mut exact = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // `--flag=value` is the same as `--flag value`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-p" | "--precision" => {
+                let value = value()?;
+                precision = Some(
+                    value
+                        .parse::<u8>()
+                        .map_err(|_| format!("invalid precision {:?}", value))?,
+                );
+            }
+            "-e" | "--exact" => exact = true,
+            "-d" | "--decimal-separator" => result.decimal_separator = Some(char_value(&value()?)?),
+            "-g" | "--grouping-separator" => {
+                result.grouping_separator = Some(char_value(&value()?)?);
+            }
+            "--grouping" => result.grouping = Some(grouping_value(&value()?)?),
+            "--padding" => result.padding_char = Some(char_value(&value()?)?),
+            "--no-right-pad" => result.right_pad = false,
+            "-f" | "--fields" => result.fields = Some(FieldSplit::Whitespace),
+            "-F" | "--delimiter" => {
+                let value = value()?;
+                if value.is_empty() {
+                    return Err("the delimiter must not be empty".to_string());
+                }
+                result.fields = Some(FieldSplit::Delimiter(value));
+            }
+            "-r" | "--regex" => {
+                let value = value()?;
+                let regex = Regex::new(&value)
+                    .map_err(|message| format!("invalid regex {:?}: {}", value, message))?;
+                result.fields = Some(FieldSplit::Regex(regex));
+            }
+            "-o" | "--output-separator" => result.output_separator = Some(value()?),
+            _ if flag.starts_with('-') && flag != "-" => {
+                return Err(format!("unknown option {}", flag))
+            }
+            _ => result.files.push(PathBuf::from(arg)),
+        }
+    }
+
+    if result.grouping_separator.is_some()
+        && result.grouping_separator == Some(result.decimal_separator.unwrap_or('.'))
+    {
+        return Err("the grouping separator must differ from the decimal separator".to_string());
+    }
+    result.precision = match (precision, exact) {
+        (Some(precision), true) => Some(FormatPrecision::Exact(precision)),
+        (Some(precision), false) => Some(FormatPrecision::Max(precision)),
+        (None, true) => return Err("--exact requires --precision".to_string()),
+        (None, false) => None,
+    };
+    Ok(Command::Align(result))
+}
+
+fn char_value(value: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(char), None) => Ok(char),
+        _ => Err(format!("expected a single character, got {:?}", value)),
+    }
+}
+
+fn grouping_value(value: &str) -> Result<Grouping, String> {
+    match value {
+        "indian" => Ok(Grouping::Indian),
+        "myriad" => Ok(Grouping::Myriad),
+        _ => value
+            .parse()
+            .map(Grouping::Fixed)
+            .map_err(|_| format!("invalid grouping {:?}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Ok(Command::Align(Args {
+                right_pad: true,
+                ..Args::default()
+            })),
+            parse_args(&[])
+        );
+        assert_eq!(
+            Ok(Command::Align(Args {
+                precision: Some(FormatPrecision::Exact(2)),
+                decimal_separator: Some(','),
+                grouping_separator: Some('.'),
+                grouping: Some(Grouping::Indian),
+                padding_char: Some('_'),
+                right_pad: false,
+                fields: None,
+                output_separator: None,
+                files: vec![
+                    PathBuf::from("a.txt"),
+                    PathBuf::from("-"),
+                    PathBuf::from("b.txt"),
+                ],
+            })),
+            parse_args(&[
+                "a.txt",
+                "-p",
+                "2",
+                "--exact",
+                "-d",
+                ",",
+                "--grouping-separator=.",
+                "--grouping",
+                "indian",
+                "--padding=_",
+                "--no-right-pad",
+                "-",
+                "b.txt",
+            ])
+        );
+        assert_eq!(Ok(Command::Help), parse_args(&["-p", "2", "--help"]));
+        assert_eq!(Ok(Command::Version), parse_args(&["-V"]));
+
+        assert!(parse_args(&["-p"]).is_err());
+        assert!(parse_args(&["-p", "x"]).is_err());
+        assert!(parse_args(&["--exact"]).is_err());
+        assert!(parse_args(&["-d", ",,"]).is_err());
+        assert!(parse_args(&["-g", "."]).is_err());
+        assert!(parse_args(&["-d", ",", "-g", ","]).is_err());
+        assert!(parse_args(&["--grouping", "x"]).is_err());
+        assert!(parse_args(&["--unknown"]).is_err());
+
+        assert_eq!(
+            Ok(Command::Align(Args {
+                right_pad: true,
+                fields: Some(FieldSplit::Regex(Regex::new("[,;]").unwrap())),
+                output_separator: Some(" | ".to_string()),
+                ..Args::default()
+            })),
+            parse_args(&["-f", "--regex", "[,;]", "-o", " | "])
+        );
+        assert_eq!(
+            Ok(Command::Align(Args {
+                right_pad: true,
+                fields: Some(FieldSplit::Delimiter("\t".to_string())),
+                ..Args::default()
+            })),
+            parse_args(&["--delimiter=\t"])
+        );
+        assert!(parse_args(&["-F", ""]).is_err());
+        assert!(parse_args(&["-r", "(a)"]).is_err());
+        assert!(parse_args(&["-r", "a|b"]).is_err());
+    }
+}
@@ -0,0 +1,302 @@
+//! Field mode: aligns the numbers inside the fields of arbitrary text, e.g. of log
+//! lines like `req=/api latency=12.5ms size=1024`.
+
+use crate::args::Args;
+use crate::regex::Regex;
+use crate::{align_numbers, Line};
+use fraction_list_fmt_align::{display_width as width, AlignError};
+
+/// How lines are split into fields.
+#[derive(Debug, PartialEq)]
+pub enum FieldSplit {
+    /// At runs of whitespace. Leading and trailing whitespace is ignored.
+    Whitespace,
+    /// At every occurrence of the string.
+    Delimiter(String),
+    /// At every match of the regular expression.
+    Regex(Regex),
+}
+
+impl FieldSplit {
+    fn split<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        match self {
+            Self::Whitespace => text.split_whitespace().collect(),
+            Self::Delimiter(delimiter) => text.split(delimiter.as_str()).collect(),
+            Self::Regex(regex) => regex.split(text),
+        }
+    }
+
+    /// The separator between the aligned fields if there is no
+    /// `--output-separator`.
+    fn default_separator(&self) -> &str {
+        match self {
+            Self::Delimiter(delimiter) => delimiter,
+            Self::Whitespace | Self::Regex(_) => " ",
+        }
+    }
+}
+
+/// A field split at its first number, e.g. `latency=`, `12.5` and `ms`. Fields
+/// without a number only have a prefix.
+#[derive(Debug, PartialEq)]
+struct Field<'a> {
+    prefix: &'a str,
+    number: Option<&'a str>,
+    suffix: &'a str,
+}
+
+impl<'a> Field<'a> {
+    fn parse(field: &'a str, args: &Args) -> Self {
+        match find_number(field, args) {
+            Some((start, end)) => Self {
+                prefix: &field[..start],
+                number: Some(&field[start..end]),
+                suffix: &field[end..],
+            },
+            None => Self {
+                prefix: field,
+                number: None,
+                suffix: "",
+            },
+        }
+    }
+}
+
+/// Aligns the numbers of every field across all lines. The text around the numbers
+/// is kept, blank lines are kept as well.
+pub(crate) fn align_fields(
+    lines: &[Line],
+    args: &Args,
+    split: &FieldSplit,
+) -> Result<Vec<String>, String> {
+    let rows = lines
+        .iter()
+        .map(|line| {
+            if line.text.trim().is_empty() {
+                Vec::new()
+            } else {
+                split
+                    .split(&line.text)
+                    .into_iter()
+                    .map(|field| Field::parse(field, args))
+                    .collect()
+            }
+        })
+        .collect::<Vec<Vec<_>>>();
+
+    let mut output = vec![String::new(); lines.len()];
+    let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let separator = args
+        .output_separator
+        .as_deref()
+        .unwrap_or_else(|| split.default_separator());
+    for index in 0..column_count {
+        let fields = rows
+            .iter()
+            .enumerate()
+            .filter_map(|(row, fields)| fields.get(index).map(|field| (row, field)))
+            .collect::<Vec<_>>();
+        let column = align_column(&fields, args).map_err(|err| match err {
+            AlignError::InvalidNumber {
+                index,
+                input,
+                reason,
+            } => {
+                let row = fields.iter().filter(|(_, f)| f.number.is_some()).nth(index);
+                let location = row.map_or("", |(row, _)| &lines[*row].location);
+                format!("{}: invalid number {:?}: {}", location, input, reason)
+            }
+        })?;
+        for ((row, _), field) in fields.iter().zip(column) {
+            if index > 0 {
+                output[*row].push_str(separator);
+            }
+            output[*row].push_str(&field);
+        }
+    }
+    for line in &mut output {
+        line.truncate(line.trim_end().len());
+    }
+    Ok(output)
+}
+
+/// Aligns the fields of one column. The prefixes, numbers and suffixes are aligned
+/// in their own columns, fields without a number are aligned to the left.
+fn align_column(fields: &[(usize, &Field)], args: &Args) -> Result<Vec<String>, AlignError> {
+    let numbers = fields
+        .iter()
+        .filter_map(|(_, field)| field.number)
+        .collect::<Vec<_>>();
+    let mut aligned = align_numbers(&numbers, args)?.into_iter();
+
+    let with_numbers = || fields.iter().filter(|(_, field)| field.number.is_some());
+    let prefix_width = with_numbers()
+        .map(|(_, field)| width(field.prefix))
+        .max()
+        .unwrap_or(0);
+    let suffix_width = with_numbers()
+        .map(|(_, field)| width(field.suffix))
+        .max()
+        .unwrap_or(0);
+
+    let rendered = fields
+        .iter()
+        .map(|(_, field)| match field.number {
+            Some(_) => {
+                let number = aligned.next().unwrap_or_default();
+                format!(
+                    "{}{}{}{}{}",
+                    field.prefix,
+                    " ".repeat(prefix_width - width(field.prefix)),
+                    number,
+                    field.suffix,
+                    " ".repeat(suffix_width - width(field.suffix)),
+                )
+            }
+            None => field.prefix.to_string(),
+        })
+        .collect::<Vec<_>>();
+    let column_width = rendered.iter().map(|f| width(f)).max().unwrap_or(0);
+    Ok(rendered
+        .into_iter()
+        .map(|field| {
+            let padding = " ".repeat(column_width - width(&field));
+            field + &padding
+        })
+        .collect())
+}
+
+/// Returns the byte range of the first number in the field, e.g. `12.5` in
+/// `latency=12.5ms`. A sign belongs to the number if it is not preceded by a letter
+/// or digit, e.g. in `delta=-3` but not in `a-3`.
+fn find_number(field: &str, args: &Args) -> Option<(usize, usize)> {
+    let decimal_separator = args.decimal_separator.unwrap_or('.');
+    let chars = field.char_indices().collect::<Vec<_>>();
+    let char_at = |index: usize| chars.get(index).map(|(_, char)| *char);
+    let is_digit_at = |index: usize| char_at(index).map_or(false, |c| c.is_ascii_digit());
+    let byte_offset = |index: usize| chars.get(index).map_or(field.len(), |(offset, _)| *offset);
+
+    for start in 0..chars.len() {
+        let mut index = start;
+        let is_sign = matches!(char_at(index), Some('-') | Some('+'));
+        let follows_word = start > 0 && char_at(start - 1).map_or(false, char::is_alphanumeric);
+        if is_sign && !follows_word {
+            index += 1;
+        }
+
+        // whole part, grouping separators only between digits
+        let mut digits = 0;
+        while is_digit_at(index)
+            || (digits > 0
+                && args.grouping_separator.is_some()
+                && char_at(index) == args.grouping_separator
+                && is_digit_at(index + 1))
+        {
+            digits += usize::from(is_digit_at(index));
+            index += 1;
+        }
+        if char_at(index) == Some(decimal_separator) && is_digit_at(index + 1) {
+            index += 1;
+            while is_digit_at(index) {
+                digits += 1;
+                index += 1;
+            }
+        }
+        if digits == 0 {
+            continue;
+        }
+        if matches!(char_at(index), Some('e') | Some('E')) {
+            let sign = usize::from(matches!(char_at(index + 1), Some('-') | Some('+')));
+            if is_digit_at(index + 1 + sign) {
+                index += 1 + sign;
+                while is_digit_at(index) {
+                    index += 1;
+                }
+            }
+        }
+        return Some((byte_offset(start), byte_offset(index)));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::tests::lines;
+
+    fn number<'a>(field: &'a str, args: &Args) -> Option<&'a str> {
+        find_number(field, args).map(|(start, end)| &field[start..end])
+    }
+
+    #[test]
+    fn test_find_number() {
+        let args = Args::default();
+        assert_eq!(Some("12.5"), number("latency=12.5ms", &args));
+        assert_eq!(Some("-3"), number("delta=-3", &args));
+        assert_eq!(Some("3"), number("a-3", &args));
+        assert_eq!(Some(".5"), number("x.5", &args));
+        assert_eq!(Some("1.5e-3"), number("1.5e-3s", &args));
+        assert_eq!(Some("1"), number("1em", &args));
+        assert_eq!(Some("1"), number("1,000", &args));
+        assert_eq!(None, number("req=/api", &args));
+        assert_eq!(None, number("-", &args));
+
+        let args = Args {
+            decimal_separator: Some(','),
+            grouping_separator: Some('.'),
+            ..Args::default()
+        };
+        assert_eq!(Some("1.000,5"), number("1.000,5€", &args));
+        assert_eq!(Some("1"), number("1.", &args));
+    }
+
+    #[test]
+    fn test_align_fields() {
+        let args = Args {
+            right_pad: true,
+            ..Args::default()
+        };
+        let input = lines(&[
+            "req=/api latency=12.5ms size=1024",
+            "",
+            "req=/a latency=1200us size=7 extra",
+            "req=/health latency=-0.25ms",
+        ]);
+        assert_eq!(
+            Ok(vec![
+                "req=/api    latency=  12.5 ms size=1024".to_string(),
+                "".to_string(),
+                "req=/a      latency=1200   us size=   7 extra".to_string(),
+                "req=/health latency=  -0.25ms".to_string(),
+            ]),
+            align_fields(&input, &args, &FieldSplit::Whitespace)
+        );
+
+        let args = Args {
+            output_separator: Some(" | ".to_string()),
+            right_pad: false,
+            ..Args::default()
+        };
+        let split = FieldSplit::Regex(Regex::new(",\\s*").unwrap());
+        assert_eq!(
+            Ok(vec!["a   |  1.5".to_string(), "bcd | -2".to_string()]),
+            align_fields(&lines(&["a, 1.5", "bcd,-2"]), &args, &split)
+        );
+        let split = FieldSplit::Delimiter(";".to_string());
+        assert_eq!(
+            Ok(vec!["x;  1".to_string(), "y;100".to_string()]),
+            align_fields(&lines(&["x;1", "y;100"]), &Args::default(), &split)
+        );
+
+        // fields are padded by their display width
+        assert_eq!(
+            Ok(vec!["価格 |  1.5".to_string(), "x    | 20".to_string()]),
+            align_fields(
+                &lines(&["価格 1.5", "x 20"]),
+                &args,
+                &FieldSplit::Whitespace
+            )
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! `fraction-align`: aligns numbers from stdin or files on their decimal separator,
+//! like `column -t` for numbers. See [`args::USAGE`].
+
+mod args;
+mod fields;
+mod regex;
+
+use args::{Args, Command};
+use fraction_list_fmt_align::{
+    fmt_align_numbers_with, try_fmt_align_fraction_strings_with, AlignError, AlignableNumber,
+};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::process;
+
+fn main() {
+    let command = match args::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("fraction-align: {}\n\n{}", message, args::USAGE);
+            process::exit(2);
+        }
+    };
+    match command {
+        Command::Help => println!("{}", args::USAGE),
+        Command::Version => println!("fraction-align {}", env!("CARGO_PKG_VERSION")),
+        Command::Align(args) => {
+            if let Err(message) = run(&args) {
+                eprintln!("fraction-align: {}", message);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// A line of the input together with its location for error messages, e.g.
+/// `data.txt:3`.
+#[derive(Debug)]
+struct Line {
+    location: String,
+    text: String,
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let lines = read_lines(args)?;
+    let aligned = match &args.fields {
+        Some(split) => fields::align_fields(&lines, args, split)?,
+        None => align_lines(&lines, args)?,
+    };
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    aligned
+        .iter()
+        .try_for_each(|line| writeln!(out, "{}", line))
+        .and_then(|_| out.flush())
+        .or_else(|err| match err.kind() {
+            // e.g. piped into `head`
+            io::ErrorKind::BrokenPipe => Ok(()),
+            _ => Err(err.to_string()),
+        })
+}
+
+/// Reads the lines of all input files, or of stdin if there are none.
+fn read_lines(args: &Args) -> Result<Vec<Line>, String> {
+    let stdin = Path::new("-");
+    let files = if args.files.is_empty() {
+        vec![stdin]
+    } else {
+        args.files.iter().map(|file| file.as_path()).collect()
+    };
+
+    let mut lines = Vec::new();
+    for file in files {
+        let (name, content) = if file == stdin {
+            let mut content = String::new();
+            io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|err| format!("stdin: {}", err))?;
+            ("stdin".to_string(), content)
+        } else {
+            let content =
+                fs::read_to_string(file).map_err(|err| format!("{}: {}", file.display(), err))?;
+            (file.display().to_string(), content)
+        };
+        lines.extend(content.lines().enumerate().map(|(index, text)| Line {
+            location: format!("{}:{}", name, index + 1),
+            text: text.to_string(),
+        }));
+    }
+    Ok(lines)
+}
+
+/// Aligns the numbers of all lines. Surrounding whitespace is ignored and blank
+/// lines are kept.
+fn align_lines(lines: &[Line], args: &Args) -> Result<Vec<String>, String> {
+    let numbers = lines
+        .iter()
+        .filter(|line| !line.text.trim().is_empty())
+        .collect::<Vec<_>>();
+    let strings = numbers
+        .iter()
+        .map(|line| line.text.trim())
+        .collect::<Vec<_>>();
+
+    let aligned = align_numbers(&strings, args).map_err(|err| match err {
+        AlignError::InvalidNumber {
+            index,
+            input,
+            reason,
+        } => format!(
+            "{}: invalid number {:?}: {}",
+            numbers[index].location, input, reason
+        ),
+    })?;
+
+    let mut aligned = aligned.into_iter();
+    Ok(lines
+        .iter()
+        .map(|line| {
+            if line.text.trim().is_empty() {
+                String::new()
+            } else {
+                aligned.next().unwrap_or_default()
+            }
+        })
+        .collect())
+}
+
+/// Validates and aligns the numbers. With a precision, the numbers are reformatted.
+fn align_numbers(strings: &[&str], args: &Args) -> Result<Vec<String>, AlignError> {
+    let options = args.options();
+    let aligned = try_fmt_align_fraction_strings_with(strings, &options)?;
+    Ok(match args.precision {
+        Some(precision) => {
+            let parsed = strings
+                .iter()
+                .map(|string| parse_number(string, args))
+                .collect::<Vec<_>>();
+            fmt_align_numbers_with(&parsed, precision, &options)
+        }
+        None => aligned,
+    })
+}
+
+/// A parsed number, see [`parse_number`].
+#[derive(Debug, PartialEq)]
+enum Number {
+    /// A number in plain decimal notation with `.` as the decimal separator, which is
+    /// rounded exactly.
+    Decimal(String),
+    /// Any other number, such as `1.5e3` or `inf`.
+    Float(f64),
+}
+
+impl AlignableNumber for Number {
+    fn is_negative(&self) -> bool {
+        match self {
+            Self::Decimal(decimal) => decimal.is_negative(),
+            Self::Float(float) => float.is_negative(),
+        }
+    }
+
+    fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Self::Decimal(decimal) => decimal.write_fixed(precision, out),
+            Self::Float(float) => float.write_fixed(precision, out),
+        }
+    }
+
+    fn write_scientific(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Self::Decimal(decimal) => decimal.write_scientific(precision, out),
+            Self::Float(float) => float.write_scientific(precision, out),
+        }
+    }
+}
+
+/// Parses a valid number string, see [`try_fmt_align_fraction_strings_with`].
+/// Numbers in plain decimal notation, including integers, are kept as strings, so that
+/// they are rounded exactly.
+fn parse_number(string: &str, args: &Args) -> Number {
+    let decimal_separator = args.decimal_separator.unwrap_or('.');
+    let normalized = string
+        .chars()
+        .filter(|c| Some(*c) != args.grouping_separator)
+        .map(|c| if c == decimal_separator { '.' } else { c })
+        .collect::<String>();
+    let is_decimal = normalized
+        .trim_start_matches(|c| c == '-' || c == '+')
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '.');
+    if is_decimal {
+        Number::Decimal(normalized)
+    } else {
+        Number::Float(normalized.parse().unwrap_or(f64::NAN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use fraction_list_fmt_align::{FormatPrecision, Grouping};
+
+    /// Returns the lines of stdin with the texts, also for the tests of other modules.
+    pub(crate) fn lines(texts: &[&str]) -> Vec<Line> {
+        texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| Line {
+                location: format!("stdin:{}", index + 1),
+                text: text.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_align_lines() {
+        let args = Args {
+            right_pad: true,
+            ..Args::default()
+        };
+        assert_eq!(
+            Ok(vec![
+                " -42     ".to_string(),
+                "".to_string(),
+                "   0.3214".to_string(),
+                "1000     ".to_string(),
+            ]),
+            align_lines(&lines(&["-42", "  ", " 0.3214\t", "1000.000"]), &args)
+        );
+        assert_eq!(
+            Err("stdin:3: invalid number \"abc\": invalid character 'a'".to_string()),
+            align_lines(&lines(&["1", "", "abc"]), &args)
+        );
+
+        let args = Args {
+            precision: Some(FormatPrecision::Exact(2)),
+            decimal_separator: Some(','),
+            grouping_separator: Some('.'),
+            grouping: Some(Grouping::Fixed(3)),
+            right_pad: true,
+            ..Args::default()
+        };
+        assert_eq!(
+            Ok(vec![
+                "    1,50".to_string(),
+                "1.234,57".to_string(),
+                "   -7,00".to_string(),
+            ]),
+            align_lines(&lines(&["1,5", "1234,567", "-7"]), &args)
+        );
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let args = Args::default();
+        assert_eq!(
+            Number::Decimal("-42".to_string()),
+            parse_number("-42", &args)
+        );
+        assert_eq!(
+            Number::Decimal("+170141183460469231731687303715884105728".to_string()),
+            parse_number("+170141183460469231731687303715884105728", &args)
+        );
+        assert_eq!(Number::Float(1500.0), parse_number("1.5e3", &args));
+
+        let args = Args {
+            decimal_separator: Some(','),
+            grouping_separator: Some('.'),
+            ..Args::default()
+        };
+        assert_eq!(
+            Number::Decimal("1234.5".to_string()),
+            parse_number("1.234,5", &args)
+        );
+    }
+
+    #[test]
+    fn test_align_lines_rounds_exactly() {
+        let args = Args {
+            precision: Some(FormatPrecision::Max(2)),
+            ..Args::default()
+        };
+        assert_eq!(
+            Ok(vec![
+                "2.68".to_string(),
+                "1.01".to_string(),
+                "0.13".to_string()
+            ]),
+            align_lines(&lines(&["2.675", "1.005", "0.125"]), &args)
+        );
+
+        let args = Args {
+            precision: Some(FormatPrecision::Max(1)),
+            ..Args::default()
+        };
+        assert_eq!(
+            Ok(vec![
+                "12345678901234567.5".to_string(),
+                "               -2.5".to_string(),
+                "             1500".to_string(),
+            ]),
+            align_lines(&lines(&["12345678901234567.5", "-2.45", "1.5e3"]), &args)
+        );
+    }
+
+    #[test]
+    fn test_align_lines_indian_grouping() {
+        let args = args::parse(
+            ["-g", ",", "--grouping", "indian"]
+                .iter()
+                .map(|arg| arg.to_string()),
+        );
+        let args = match args {
+            Ok(Command::Align(args)) => args,
+            _ => panic!("invalid arguments"),
+        };
+        assert_eq!(
+            Ok(vec!["12,34,567.5".to_string(), "   -1,000  ".to_string()]),
+            align_lines(&lines(&["12,34,567.5", "-1,000"]), &args)
+        );
+    }
+}
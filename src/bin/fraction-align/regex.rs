@@ -0,0 +1,266 @@
+//! Matching of field separators with a small subset of regular expressions, like the
+//! `FS` of awk. Field separators rarely need more, so that this avoids a dependency.
+//!
+//! A pattern is a sequence of atoms, each optionally followed by one quantifier:
+//! * Atoms: literal characters, `.` for any character, character classes such as
+//!   `[,;]`, `[a-z]` or `[^0-9]`, the escapes `\d`, `\s`, `\w`, their negations `\D`,
+//!   `\S`, `\W`, and `\t`. Special characters are escaped with `\`, e.g. `\.` or
+//!   `\|`.
+//! * Quantifiers: `*`, `+` and `?`, which are greedy.
+//!
+//! All other syntax is rejected with an error: alternatives with `|` (use a class
+//! instead, e.g. `[,;]`), groups, anchors, counted repetitions with `{}`, lazy
+//! quantifiers and negated escapes in classes.
+
+/// A compiled regular expression.
+#[derive(Debug, PartialEq)]
+pub struct Regex {
+    pieces: Vec<Piece>,
+}
+
+/// An atom with a quantifier.
+#[derive(Debug, PartialEq)]
+struct Piece {
+    atom: Atom,
+    min: usize,
+    max: Option<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Atom {
+    Char(char),
+    Any,
+    Class {
+        negated: bool,
+        items: Vec<ClassItem>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+enum ClassItem {
+    Range(char, char),
+    Digit,
+    Space,
+    Word,
+}
+
+impl ClassItem {
+    fn matches(&self, char: char) -> bool {
+        match self {
+            Self::Range(start, end) => (*start..=*end).contains(&char),
+            Self::Digit => char.is_ascii_digit(),
+            Self::Space => char.is_whitespace(),
+            Self::Word => char.is_alphanumeric() || char == '_',
+        }
+    }
+}
+
+impl Atom {
+    fn matches(&self, char: char) -> bool {
+        match self {
+            Self::Char(expected) => char == *expected,
+            Self::Any => true,
+            Self::Class { negated, items } => {
+                items.iter().any(|item| item.matches(char)) != *negated
+            }
+        }
+    }
+}
+
+impl Regex {
+    /// Compiles a regular expression. Returns a message for the user if it is invalid.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(char) = chars.next() {
+            let atom = match char {
+                '*' | '+' | '?' => {
+                    let piece = pieces
+                        .last_mut()
+                        .filter(|piece| piece.min == 1 && piece.max == Some(1))
+                        .ok_or_else(|| format!("nothing to repeat before {:?}", char))?;
+                    match char {
+                        '*' => {
+                            piece.min = 0;
+                            piece.max = None;
+                        }
+                        '+' => piece.max = None,
+                        _ => piece.min = 0,
+                    }
+                    continue;
+                }
+                '|' => {
+                    return Err(
+                        "alternatives are not supported, use a class such as [,;]".to_string()
+                    )
+                }
+                '(' | ')' | '^' | '$' | '{' | '}' => {
+                    return Err(format!("{:?} is not supported, escape it with \\", char))
+                }
+                '.' => Atom::Any,
+                '\\' => escape(chars.next())?,
+                '[' => class(&mut chars)?,
+                char => Atom::Char(char),
+            };
+            pieces.push(Piece {
+                atom,
+                min: 1,
+                max: Some(1),
+            });
+        }
+        Ok(Self { pieces })
+    }
+
+    /// Returns the byte range of the leftmost non-empty match at or after `start`.
+    pub fn find(&self, text: &str, start: usize) -> Option<(usize, usize)> {
+        text[start..]
+            .char_indices()
+            .map(|(index, _)| start + index)
+            .find_map(|position| {
+                match_pieces(&self.pieces, text, position)
+                    .filter(|end| *end > position)
+                    .map(|end| (position, end))
+            })
+    }
+
+    /// Splits the text at all non-empty matches.
+    pub fn split<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut fields = Vec::new();
+        let mut field_start = 0;
+        while let Some((start, end)) = self.find(text, field_start) {
+            fields.push(&text[field_start..start]);
+            field_start = end;
+        }
+        fields.push(&text[field_start..]);
+        fields
+    }
+}
+
+/// Returns the end of the match of all pieces at `position`. Quantifiers are greedy
+/// and backtrack.
+fn match_pieces(pieces: &[Piece], text: &str, position: usize) -> Option<usize> {
+    let (piece, rest) = match pieces.split_first() {
+        Some(split) => split,
+        None => return Some(position),
+    };
+    // the positions after 0, 1, 2, ... repetitions
+    let mut ends = vec![position];
+    let mut current = position;
+    while piece.max.map_or(true, |max| ends.len() <= max) {
+        match text[current..].chars().next() {
+            Some(char) if piece.atom.matches(char) => {
+                current += char.len_utf8();
+                ends.push(current);
+            }
+            _ => break,
+        }
+    }
+    ends.iter()
+        .skip(piece.min)
+        .rev()
+        .find_map(|end| match_pieces(rest, text, *end))
+}
+
+fn escape(char: Option<char>) -> Result<Atom, String> {
+    let class = |negated, item| Atom::Class {
+        negated,
+        items: vec![item],
+    };
+    Ok(match char {
+        Some('d') => class(false, ClassItem::Digit),
+        Some('D') => class(true, ClassItem::Digit),
+        Some('s') => class(false, ClassItem::Space),
+        Some('S') => class(true, ClassItem::Space),
+        Some('w') => class(false, ClassItem::Word),
+        Some('W') => class(true, ClassItem::Word),
+        Some('t') => Atom::Char('\t'),
+        Some(char) if !char.is_alphanumeric() => Atom::Char(char),
+        Some(char) => return Err(format!("unknown escape \\{}", char)),
+        None => return Err("trailing \\".to_string()),
+    })
+}
+
+/// Parses a character class after the `[`.
+fn class(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<Atom, String> {
+    let negated = chars.next_if_eq(&'^').is_some();
+    let mut items = Vec::new();
+    loop {
+        let start = match chars.next() {
+            Some(']') if !items.is_empty() => break,
+            Some('\\') => match escape(chars.next())? {
+                Atom::Char(char) => char,
+                Atom::Class {
+                    negated: false,
+                    items: escaped,
+                } => {
+                    items.extend(escaped);
+                    continue;
+                }
+                _ => return Err("negated escapes are not supported in classes".to_string()),
+            },
+            Some(char) => char,
+            None => return Err("unterminated character class".to_string()),
+        };
+        let mut lookahead = chars.clone();
+        match (lookahead.next(), lookahead.next()) {
+            (Some('-'), Some(end)) if end != ']' => {
+                chars.next();
+                chars.next();
+                items.push(ClassItem::Range(start, end));
+            }
+            _ => items.push(ClassItem::Range(start, start)),
+        }
+    }
+    Ok(Atom::Class { negated, items })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn split<'a>(pattern: &str, text: &'a str) -> Vec<&'a str> {
+        Regex::new(pattern).unwrap().split(text)
+    }
+
+    #[test]
+    fn test_regex() {
+        assert_eq!(vec!["a", "b", "c"], split(r"\s+", "a  b\tc"));
+        assert_eq!(vec!["1", "2", "3"], split("[,;] ?", "1, 2;3"));
+        assert_eq!(vec!["a", "b"], split("-*>", "a-->b"));
+        assert_eq!(vec!["a", "b"], split(r"\.", "a.b"));
+        assert_eq!(vec!["x", "y"], split(r"\|", "x|y"));
+        assert_eq!(vec!["k", "v"], split("[^a-z0-9]", "k=v"));
+        assert_eq!(vec!["12", "5"], split(r"\D+", "12ms5"));
+        assert_eq!(vec!["a", "b"], split(r"[\s\t]\w?=", "a x=b"));
+        // quantifiers backtrack
+        assert_eq!(vec!["a", "b"], split("x*xy", "axxxyb"));
+        // empty matches are ignored
+        assert_eq!(vec!["ab"], split("x*", "ab"));
+        assert_eq!(Some((1, 3)), Regex::new("b.").unwrap().find("abcd", 0));
+    }
+
+    #[test]
+    fn test_regex_unsupported() {
+        let error = |pattern| Regex::new(pattern).unwrap_err();
+        assert_eq!(
+            "alternatives are not supported, use a class such as [,;]",
+            error("a|b")
+        );
+        assert_eq!("'(' is not supported, escape it with \\", error("(a)"));
+        assert_eq!("'^' is not supported, escape it with \\", error("^a"));
+        assert_eq!("'$' is not supported, escape it with \\", error("a$"));
+        assert_eq!("'{' is not supported, escape it with \\", error("a{2}"));
+        assert_eq!("nothing to repeat before '*'", error("*"));
+        // lazy and possessive quantifiers
+        assert_eq!("nothing to repeat before '?'", error("a*?"));
+        assert_eq!("nothing to repeat before '+'", error("a++"));
+        assert_eq!("unterminated character class", error("[a"));
+        assert_eq!("trailing \\", error("a\\"));
+        assert_eq!("unknown escape \\b", error(r"\b"));
+        assert_eq!(
+            "negated escapes are not supported in classes",
+            error(r"[\D]")
+        );
+    }
+}
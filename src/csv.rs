@@ -0,0 +1,355 @@
+//! Alignment of the numeric columns of CSV and TSV data, see [`CsvAligner`].
+
+use crate::error::CsvError;
+use crate::parts::FractionParts;
+use crate::width::display_width;
+use crate::{fmt_align_fraction_strings_with, AlignOptions};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::mem;
+
+/// The output of a [`CsvAligner`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CsvOutput {
+    /// The fields without quotes, padded to the width of their column and separated
+    /// by a space. For reading in a terminal. Line breaks in fields are written as `\n`
+    /// and `\r`, so that every record stays on one line.
+    FixedWidth,
+    /// Valid CSV with the delimiter of the input, in which the fields carry the
+    /// padding. If a field of a column needs quotes, all fields of the column are
+    /// quoted and padded within the quotes, so that the delimiters are still aligned.
+    Csv,
+}
+
+/// Reads CSV or TSV data and aligns its numeric columns on the decimal point.
+///
+/// A column is numeric if all of its fields, except for the header and empty
+/// fields, are valid fraction numbers by the rules of
+/// [`crate::try_fmt_align_fraction_strings_with`] in the input locale of the
+/// [`AlignOptions`]. Surrounding spaces of numbers are ignored. The numbers are aligned
+/// with [`fmt_align_fraction_strings_with`] and right-aligned in their column, all
+/// other fields are left-aligned.
+///
+/// Fields are separated by the delimiter and records by line breaks (`\n` or
+/// `\r\n`). Fields in double quotes may contain delimiters, line breaks and double
+/// quotes, which are escaped by doubling them (RFC 4180). Records with fewer fields
+/// than others are filled up with empty fields.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{CsvAligner, CsvOutput};
+///
+/// let input = "sensor,value\ntemperature,21.5\n\"pressure, max\",1013\n";
+/// let aligned = CsvAligner::new().align(input).unwrap();
+/// assert_eq!(
+///     aligned,
+///     "sensor        value \n\
+///      temperature     21.5\n\
+///      pressure, max 1013  \n"
+/// );
+///
+/// let aligned = CsvAligner::new().output(CsvOutput::Csv).align(input).unwrap();
+/// assert_eq!(
+///     aligned,
+///     "\"sensor       \",value \n\
+///      \"temperature  \",  21.5\n\
+///      \"pressure, max\",1013  \n"
+/// );
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct CsvAligner {
+    delimiter: char,
+    header: bool,
+    output: CsvOutput,
+    options: AlignOptions,
+}
+
+impl CsvAligner {
+    /// Creates an aligner for CSV with `,` as delimiter and a header in the first
+    /// record. The output is [`CsvOutput::FixedWidth`] and the numbers are aligned with
+    /// the default [`AlignOptions`].
+    pub const fn new() -> Self {
+        Self {
+            delimiter: ',',
+            header: true,
+            output: CsvOutput::FixedWidth,
+            options: AlignOptions::new(),
+        }
+    }
+
+    /// Creates an aligner for TSV, i.e. with a tab as delimiter.
+    pub const fn tsv() -> Self {
+        Self::new().delimiter('\t')
+    }
+
+    /// Sets the delimiter between two fields. Default is `,`.
+    pub const fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets whether the first record is a header, which is never numeric. Default is
+    /// `true`.
+    pub const fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the [`CsvOutput`]. Default is [`CsvOutput::FixedWidth`].
+    pub const fn output(mut self, output: CsvOutput) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Sets the [`AlignOptions`] for the detection and the alignment of the numbers,
+    /// e.g. the input locale.
+    pub const fn options(mut self, options: AlignOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Reads the CSV data and returns it with aligned columns. Every record of the
+    /// output ends with `\n`.
+    pub fn align(&self, input: &str) -> Result<String, CsvError> {
+        let mut records = parse_records(input, self.delimiter)?;
+        let column_count = records.iter().map(Vec::len).max().unwrap_or(0);
+        for record in &mut records {
+            record.resize(column_count, String::new());
+        }
+        let columns = (0..column_count)
+            .map(|index| self.render_column(&records, index))
+            .collect::<Vec<_>>();
+
+        let separator = match self.output {
+            CsvOutput::FixedWidth => ' ',
+            CsvOutput::Csv => self.delimiter,
+        };
+        let mut output = String::new();
+        for row in 0..records.len() {
+            for (index, column) in columns.iter().enumerate() {
+                if index > 0 {
+                    output.push(separator);
+                }
+                output.push_str(&column[row]);
+            }
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    /// Returns the fields of a column, padded to the width of the column.
+    fn render_column(&self, records: &[Vec<String>], index: usize) -> Vec<String> {
+        let fields = records
+            .iter()
+            .map(|record| record[index].as_str())
+            .collect::<Vec<_>>();
+        let (header, data) = fields.split_at(usize::from(self.header).min(fields.len()));
+
+        let numbers = data
+            .iter()
+            .map(|field| field.trim())
+            .filter(|field| !field.is_empty())
+            .collect::<Vec<_>>();
+        let is_numeric = !numbers.is_empty()
+            && numbers
+                .iter()
+                .all(|number| FractionParts::try_parse(number, &self.options.input_locale).is_ok());
+        let mut aligned = if is_numeric {
+            fmt_align_fraction_strings_with(&numbers, &self.options)
+        } else {
+            Vec::new()
+        }
+        .into_iter();
+
+        // the content of every field and whether it is right-aligned
+        let cells = header
+            .iter()
+            .map(|field| (field.to_string(), false))
+            .chain(data.iter().map(|field| {
+                if is_numeric && !field.trim().is_empty() {
+                    (aligned.next().unwrap_or_default(), true)
+                } else {
+                    (field.to_string(), false)
+                }
+            }))
+            .collect::<Vec<_>>();
+
+        let quote = self.output == CsvOutput::Csv
+            && cells
+                .iter()
+                .any(|(cell, _)| needs_quotes(cell, self.delimiter));
+        let cells = cells
+            .into_iter()
+            .map(|(cell, right)| match self.output {
+                CsvOutput::FixedWidth => (escape_line_breaks(&cell), right),
+                CsvOutput::Csv if quote => (cell.replace('"', "\"\""), right),
+                CsvOutput::Csv => (cell, right),
+            })
+            .collect::<Vec<_>>();
+        let width = cells
+            .iter()
+            .map(|(cell, _)| display_width(cell))
+            .max()
+            .unwrap_or(0);
+
+        cells
+            .into_iter()
+            .map(|(cell, right)| {
+                let padding = " ".repeat(width - display_width(&cell));
+                let cell = if right {
+                    padding + &cell
+                } else {
+                    cell + &padding
+                };
+                if quote {
+                    format!("\"{}\"", cell)
+                } else {
+                    cell
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for CsvAligner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a field must be quoted in CSV.
+fn needs_quotes(field: &str, delimiter: char) -> bool {
+    field.contains(|c| c == delimiter || c == '"' || c == '\n' || c == '\r')
+}
+
+/// Replaces the line breaks of a field by `\n` and `\r`, see [`CsvOutput::FixedWidth`].
+fn escape_line_breaks(field: &str) -> String {
+    field.replace('\n', "\\n").replace('\r', "\\r")
+}
+
+/// Splits CSV data into records of unquoted fields.
+fn parse_records(input: &str, delimiter: char) -> Result<Vec<Vec<String>>, CsvError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    // whether the current record has any content, so that a line break at the end of
+    // the input doesn't add an empty record
+    let mut in_record = false;
+    let mut line = 1;
+    let mut chars = input.chars().peekable();
+    while let Some(char) = chars.next() {
+        in_record = true;
+        if char == '"' && field.is_empty() {
+            let start_line = line;
+            loop {
+                match chars.next() {
+                    None => return Err(CsvError::UnterminatedQuote { line: start_line }),
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(char) => {
+                        if char == '\n' {
+                            line += 1;
+                        }
+                        field.push(char);
+                    }
+                }
+            }
+            match chars.peek() {
+                None | Some('\n') | Some('\r') => {}
+                Some(char) if *char == delimiter => {}
+                Some(_) => return Err(CsvError::CharacterAfterQuote { line }),
+            }
+        } else if char == delimiter {
+            record.push(mem::take(&mut field));
+        } else if char == '\n' || char == '\r' {
+            if char == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            line += 1;
+            record.push(mem::take(&mut field));
+            records.push(mem::take(&mut record));
+            in_record = false;
+        } else {
+            field.push(char);
+        }
+    }
+    if in_record {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Locale;
+
+    #[test]
+    fn test_parse_records() {
+        assert_eq!(Ok(vec![]), parse_records("", ','));
+        assert_eq!(
+            Ok(vec![
+                vec!["a".to_string(), "b, \"c\"\nd".to_string(), "".to_string()],
+                vec!["".to_string()],
+                vec!["1".to_string()],
+            ]),
+            parse_records("a,\"b, \"\"c\"\"\nd\",\r\n\n1", ',')
+        );
+        assert_eq!(
+            Err(CsvError::UnterminatedQuote { line: 2 }),
+            parse_records("a\n\"b\n", ',')
+        );
+        assert_eq!(
+            Err(CsvError::CharacterAfterQuote { line: 1 }),
+            parse_records("\"a\"b", ',')
+        );
+    }
+
+    #[test]
+    fn test_csv_aligner() {
+        let input = "name,value,unit\n\"a, b\",1.5,m\nc,-10,\"k\"\"g\"\nd,,";
+        assert_eq!(
+            Ok("name value unit\n\
+                a, b   1.5 m   \n\
+                c    -10   k\"g \n\
+                d              \n"
+                .to_string()),
+            CsvAligner::new().align(input)
+        );
+        assert_eq!(
+            Ok("\"name\",value,\"unit\"\n\
+                \"a, b\",  1.5,\"m   \"\n\
+                \"c   \",-10  ,\"k\"\"g\"\n\
+                \"d   \",     ,\"    \"\n"
+                .to_string()),
+            CsvAligner::new().output(CsvOutput::Csv).align(input)
+        );
+
+        // without header, a column with text is not numeric
+        let options = AlignOptions::new()
+            .input_locale(Locale::DE)
+            .output_locale(Locale::DE);
+        let aligned = CsvAligner::tsv()
+            .header(false)
+            .options(options)
+            .align("1.000,5\tx\n 2 \t3\n");
+        assert_eq!(Ok("1.000,5 x\n    2   3\n".to_string()), aligned);
+
+        // line breaks in fields don't break the records of fixed-width output
+        let input = "a,b\n\"x\ny\",1\nz,22\r\n\"\r\n\",3\n";
+        assert_eq!(
+            Ok("a    b \n\
+                x\\ny  1\n\
+                z    22\n\
+                \\r\\n  3\n"
+                .to_string()),
+            CsvAligner::new().align(input)
+        );
+    }
+}
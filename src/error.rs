@@ -0,0 +1,149 @@
+//! Error types of this crate.
+
+use crate::Notation;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+/// Errors that can occur when aligning a list of fraction numbers with the
+/// fallible functions of this crate, such as [`crate::try_fmt_align_fraction_strings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlignError {
+    /// An entry of the input list is not a valid fraction number.
+    InvalidNumber {
+        /// Index of the offending entry in the input list.
+        index: usize,
+        /// The offending entry.
+        input: String,
+        /// Why the entry was rejected.
+        reason: InvalidNumberReason,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for AlignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNumber {
+                index,
+                input,
+                reason,
+            } => write!(
+                f,
+                "invalid number {:?} at index {}: {}",
+                input, index, reason
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AlignError {}
+
+/// Errors of [`crate::align_into`], which aligns into fixed-capacity buffers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FixedAlignError {
+    /// The aligned entry does not fit into its row buffer.
+    RowOverflow {
+        /// Index of the entry in the input list.
+        index: usize,
+        /// Capacity of a row in bytes.
+        capacity: usize,
+    },
+    /// The notation requires the `alloc` feature. Only [`Notation::Fixed`] is
+    /// supported.
+    UnsupportedNotation(Notation),
+}
+
+impl fmt::Display for FixedAlignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RowOverflow { index, capacity } => write!(
+                f,
+                "entry at index {} does not fit into a row of {} bytes",
+                index, capacity
+            ),
+            Self::UnsupportedNotation(notation) => {
+                write!(f, "notation {:?} is not supported", notation)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FixedAlignError {}
+
+#[cfg(feature = "alloc")]
+/// Errors of [`crate::CsvAligner::align`] for malformed CSV input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CsvError {
+    /// A quoted field is not closed until the end of the input.
+    UnterminatedQuote {
+        /// Line of the opening quote, starting at one.
+        line: usize,
+    },
+    /// A closing quote is followed by a character other than the delimiter or a
+    /// line break, e.g. `"a"b`.
+    CharacterAfterQuote {
+        /// Line of the closing quote, starting at one.
+        line: usize,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnterminatedQuote { line } => {
+                write!(f, "quoted field in line {} is not closed", line)
+            }
+            Self::CharacterAfterQuote { line } => {
+                write!(
+                    f,
+                    "unexpected character after closing quote in line {}",
+                    line
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CsvError {}
+
+/// The reason why a string is not a valid fraction number.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InvalidNumberReason {
+    /// The string is empty.
+    Empty,
+    /// The string contains more than one decimal point, e.g. `1.2.3`.
+    MultipleDecimalPoints,
+    /// The string contains a character that is neither a digit, a sign, nor
+    /// the decimal point, e.g. `abc` or `12 `.
+    InvalidCharacter(char),
+    /// A sign appears somewhere else than at the very beginning, e.g. `1-2`.
+    MisplacedSign,
+    /// The grouping separators of the whole part are not at the positions of the
+    /// [`crate::Grouping`], e.g. `1,,2`, `,123` or `12,34` with groups of three.
+    MisplacedGroupingSeparator,
+    /// The string has no digits at all, e.g. `-` or `.`.
+    NoDigits,
+    /// The exponent of a number in scientific notation is not an optionally signed
+    /// integer, e.g. `1e` or `1e2.5`.
+    InvalidExponent,
+}
+
+impl fmt::Display for InvalidNumberReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty string"),
+            Self::MultipleDecimalPoints => write!(f, "more than one decimal point"),
+            Self::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            Self::MisplacedSign => write!(f, "sign is not at the beginning"),
+            Self::MisplacedGroupingSeparator => write!(f, "misplaced grouping separator"),
+            Self::NoDigits => write!(f, "no digits"),
+            Self::InvalidExponent => write!(f, "invalid exponent"),
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Alignment into fixed-capacity buffers without any heap, see [`align_into`].
+
+use crate::align::AlignmentLayout;
+use crate::number::write_number;
+use crate::parts::FractionParts;
+use crate::{AlignOptions, AlignableNumber, FixedAlignError, FormatPrecision, Notation};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+use core::ops::Deref;
+
+/// A string with a fixed capacity of `W` bytes that lives on the stack. Used by
+/// [`align_into`] for targets without a heap.
+///
+/// Writing via [`fmt::Write`] fails with [`fmt::Error`] if the string does not fit
+/// into the remaining capacity. In that case, nothing is written.
+#[derive(Copy, Clone)]
+pub struct FixedString<const W: usize> {
+    buf: [u8; W],
+    len: usize,
+}
+
+impl<const W: usize> FixedString<W> {
+    /// Creates an empty string.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; W],
+            len: 0,
+        }
+    }
+
+    /// Returns the content.
+    pub fn as_str(&self) -> &str {
+        // only complete strings are written into the buffer
+        core::str::from_utf8(&self.buf[..self.len]).expect("content must be valid UTF-8")
+    }
+
+    /// Returns the length in bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the string is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the capacity in bytes, i.e. `W`.
+    pub const fn capacity(&self) -> usize {
+        W
+    }
+
+    /// Removes the content.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const W: usize> fmt::Write for FixedString<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > W {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl<const W: usize> Deref for FixedString<W> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const W: usize> Default for FixedString<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize> fmt::Debug for FixedString<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const W: usize> fmt::Display for FixedString<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const W: usize> PartialEq for FixedString<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const W: usize> Eq for FixedString<W> {}
+
+impl<const W: usize> PartialEq<str> for FixedString<W> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const W: usize> PartialEq<&str> for FixedString<W> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const W: usize> PartialEq<FixedString<W>> for str {
+    fn eq(&self, other: &FixedString<W>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl<const W: usize> PartialEq<FixedString<W>> for &str {
+    fn eq(&self, other: &FixedString<W>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+/// Like [`crate::fmt_align_fractions_with`] but aligns `N` numbers into `N` rows with a
+/// capacity of `W` bytes each, without any heap allocation. This works on bare-metal
+/// targets without an allocator.
+///
+/// Only [`Notation::Fixed`] is supported. A row buffer must also be able to hold the
+/// formatted number before unnecessary zeroes are removed.
+///
+/// Returns [`FixedAlignError::RowOverflow`] for the first entry that does not fit.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{align_into, AlignOptions, FixedString, FormatPrecision};
+///
+/// let rows: [FixedString<16>; 3] =
+///     align_into(&[-42.0, 0.3214, 1000.0], FormatPrecision::Max(4), &AlignOptions::new())
+///         .unwrap();
+/// assert_eq!(rows, [" -42     ", "   0.3214", "1000     "]);
+/// ```
+pub fn align_into<T: AlignableNumber, const N: usize, const W: usize>(
+    numbers: &[T; N],
+    precision: FormatPrecision,
+    options: &AlignOptions,
+) -> Result<[FixedString<W>; N], FixedAlignError> {
+    if options.notation != Notation::Fixed {
+        return Err(FixedAlignError::UnsupportedNotation(options.notation));
+    }
+    let options = options.for_precision(precision);
+    let overflow = |index| FixedAlignError::RowOverflow { index, capacity: W };
+
+    // the numbers are formatted twice to not store them
+    let mut layout = AlignmentLayout::empty(&options);
+    for (index, number) in numbers.iter().enumerate() {
+        let formatted = format_fixed::<T, W>(number, precision).map_err(|_| overflow(index))?;
+        layout = layout.with_parts(FractionParts::parse(&formatted, '.'));
+    }
+
+    let mut rows = [FixedString::new(); N];
+    for (index, (number, row)) in numbers.iter().zip(rows.iter_mut()).enumerate() {
+        let formatted = format_fixed::<T, W>(number, precision).map_err(|_| overflow(index))?;
+        layout
+            .write_parts(row, FractionParts::parse(&formatted, '.'))
+            .map_err(|_| overflow(index))?;
+    }
+    Ok(rows)
+}
+
+/// Formats the number including its sign in plain decimal notation.
+fn format_fixed<T: AlignableNumber, const W: usize>(
+    number: &T,
+    precision: FormatPrecision,
+) -> Result<FixedString<W>, fmt::Error> {
+    let mut string = FixedString::new();
+    write_number(number, precision, Notation::Fixed, &mut string)?;
+    Ok(string)
+}
+
+/// The capacity of a [`NumberBuffer`] on the stack, which holds all primitive numbers
+/// in plain decimal notation with a precision of up to 80, except very large floats.
+const NUMBER_CAPACITY: usize = 128;
+
+/// A buffer for one formatted number on the stack, so that numbers can be formatted
+/// and measured without allocating a `String` per number. With the `alloc` feature,
+/// numbers that don't fit, e.g. `f64::MAX` in plain decimal notation, are moved to the
+/// heap; without, writing them fails.
+#[derive(Debug, Default)]
+pub(crate) struct NumberBuffer {
+    stack: FixedString<NUMBER_CAPACITY>,
+    #[cfg(feature = "alloc")]
+    heap: Option<String>,
+}
+
+impl NumberBuffer {
+    pub(crate) const fn new() -> Self {
+        Self {
+            stack: FixedString::new(),
+            #[cfg(feature = "alloc")]
+            heap: None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        #[cfg(feature = "alloc")]
+        if let Some(heap) = &self.heap {
+            return heap;
+        }
+        self.stack.as_str()
+    }
+}
+
+impl fmt::Write for NumberBuffer {
+    #[cfg(feature = "alloc")]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(heap) = &mut self.heap {
+            heap.push_str(s);
+        } else if self.stack.write_str(s).is_err() {
+            let mut heap = String::from(self.stack.as_str());
+            heap.push_str(s);
+            self.heap = Some(heap);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.stack.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::FractionNumber;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_fixed_string() {
+        let mut string = FixedString::<4>::new();
+        assert!(string.is_empty());
+        assert!(write!(string, "ab").is_ok());
+        assert!(string.write_str("cde").is_err());
+        assert_eq!("ab", string.as_str());
+        assert!(string.write_str("µ").is_ok());
+        assert_eq!(4, string.len());
+        assert!(string.write_char('x').is_err());
+        string.clear();
+        assert_eq!(FixedString::<4>::new(), string);
+    }
+
+    #[test]
+    fn test_number_buffer() {
+        let mut buffer = NumberBuffer::new();
+        assert!(buffer.write_str(&"1".repeat(NUMBER_CAPACITY)).is_ok());
+        #[cfg(feature = "alloc")]
+        {
+            assert!(buffer.write_str(".5").is_ok());
+            assert_eq!(NUMBER_CAPACITY + 2, buffer.as_str().len());
+            assert!(buffer.as_str().ends_with("11.5"));
+        }
+        #[cfg(not(feature = "alloc"))]
+        assert!(buffer.write_str(".5").is_err());
+    }
+
+    #[test]
+    fn test_align_into() {
+        let numbers = [
+            FractionNumber::F32(-42.0),
+            FractionNumber::F64(0.3214),
+            FractionNumber::I128(1000),
+            FractionNumber::F64(-1000.2),
+            FractionNumber::F64(2.0),
+        ];
+        let rows: [FixedString<10>; 5] =
+            align_into(&numbers, FormatPrecision::Max(4), &AlignOptions::new()).unwrap();
+        assert_eq!(
+            [
+                "  -42     ",
+                "    0.3214",
+                " 1000     ",
+                "-1000.2   ",
+                "    2     "
+            ],
+            rows
+        );
+
+        let rows: [FixedString<8>; 2] = align_into(
+            &[1.5_f64, -22.25],
+            FormatPrecision::Exact(2),
+            &AlignOptions::new().grouping_separator(Some(',')),
+        )
+        .unwrap();
+        assert_eq!(["  1.50", "-22.25"], rows);
+
+        assert_eq!(
+            Err(FixedAlignError::RowOverflow {
+                index: 1,
+                capacity: 6
+            }),
+            align_into::<_, 2, 6>(&[1.0, 1e6], FormatPrecision::Max(0), &AlignOptions::new())
+        );
+        let options = AlignOptions::new().notation(Notation::Scientific);
+        assert_eq!(
+            Err(FixedAlignError::UnsupportedNotation(Notation::Scientific)),
+            align_into::<_, 1, 8>(&[1.0], FormatPrecision::Max(0), &options)
+        );
+    }
+}
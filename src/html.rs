@@ -0,0 +1,328 @@
+//! HTML rendering of aligned entries, see [`HtmlFormat`].
+
+use crate::align::{AlignmentLayout, Segment};
+#[cfg(feature = "alloc")]
+use crate::number::FormattedNumbers;
+use crate::options::OutputStyle;
+use crate::parts::FractionParts;
+#[cfg(feature = "alloc")]
+use crate::{AlignOptions, AlignableNumber, FormatPrecision};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write};
+
+/// Describes the HTML markup of aligned entries, see [`fmt_align_numbers_html`].
+///
+/// Every entry is rendered as a `<span>` with the class prefix as class. It contains a
+/// `<span>` for every part of the entry, with the classes `<prefix>-whole` (sign
+/// and whole part), `<prefix>-point` (decimal separator), `<prefix>-fraction`
+/// (fractional part) and `<prefix>-suffix` (exponent or SI prefix and unit). Empty
+/// parts are omitted. The classes are hooks for CSS, e.g. to color the fractional
+/// part.
+///
+/// Browsers collapse spaces and don't support aligning on the decimal separator.
+/// Hence, the entries are always rendered with [`OutputStyle::Typographic`], i.e. the
+/// padding consists of figure spaces and punctuation spaces, which browsers keep.
+/// The entries are aligned in every font with tabular figures, e.g. with the CSS
+/// property `font-variant-numeric: tabular-nums`.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{AlignmentLayout, AlignOptions, HtmlFormat};
+///
+/// let input = ["-1.5", "10"];
+/// let layout = AlignmentLayout::new(&input, &AlignOptions::new());
+/// let html = layout.display_html("10", HtmlFormat::new().class_prefix("n")).to_string();
+/// assert_eq!(
+///     html,
+///     "<span class=\"n\"><span class=\"n-whole\">10</span>\
+///      <span class=\"n-point\">\u{2008}</span>\
+///      <span class=\"n-fraction\">\u{2007}</span></span>"
+/// );
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HtmlFormat<'a> {
+    class_prefix: &'a str,
+}
+
+impl<'a> HtmlFormat<'a> {
+    /// Creates the default HTML format with the class prefix `number`.
+    pub const fn new() -> Self {
+        Self {
+            class_prefix: "number",
+        }
+    }
+
+    /// Sets the class of the entries and the prefix of the classes of their parts.
+    /// Default is `"number"`.
+    pub const fn class_prefix(mut self, class_prefix: &'a str) -> Self {
+        self.class_prefix = class_prefix;
+        self
+    }
+}
+
+impl Default for HtmlFormat<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One aligned entry of an [`AlignmentLayout`] rendered as HTML, see
+/// [`AlignmentLayout::display_html`]. All text is escaped.
+#[derive(Debug, Copy, Clone)]
+pub struct HtmlEntry<'a> {
+    layout: AlignmentLayout,
+    parts: FractionParts<'a>,
+    format: HtmlFormat<'a>,
+}
+
+impl<'a> HtmlEntry<'a> {
+    pub(crate) const fn new(
+        layout: AlignmentLayout,
+        parts: FractionParts<'a>,
+        format: HtmlFormat<'a>,
+    ) -> Self {
+        Self {
+            layout: layout.with_output_style(OutputStyle::Typographic),
+            parts,
+            format,
+        }
+    }
+}
+
+impl fmt::Display for HtmlEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = self.format.class_prefix;
+        f.write_str("<span class=\"")?;
+        Escaped(f).write_str(prefix)?;
+        f.write_str("\">")?;
+        let mut out = SpanWriter {
+            f,
+            prefix,
+            pending: None,
+            is_open: false,
+        };
+        self.layout
+            .write_segments(&mut out, self.parts, |out, segment| out.start(segment))?;
+        out.close()?;
+        f.write_str("</span>")
+    }
+}
+
+/// Writes the segments of an entry into their own spans. The span of a segment is
+/// opened with its first character, so that empty segments are omitted.
+struct SpanWriter<'a, 'f, 'b> {
+    f: &'a mut fmt::Formatter<'f>,
+    prefix: &'b str,
+    pending: Option<Segment>,
+    is_open: bool,
+}
+
+impl SpanWriter<'_, '_, '_> {
+    fn start(&mut self, segment: Segment) -> fmt::Result {
+        self.close()?;
+        self.pending = Some(segment);
+        Ok(())
+    }
+
+    fn close(&mut self) -> fmt::Result {
+        if self.is_open {
+            self.is_open = false;
+            self.f.write_str("</span>")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Write for SpanWriter<'_, '_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.is_empty() {
+            return Ok(());
+        }
+        if let Some(segment) = self.pending.take() {
+            let class = match segment {
+                Segment::Whole => "whole",
+                Segment::Point => "point",
+                Segment::Fraction => "fraction",
+                Segment::Suffix => "suffix",
+            };
+            self.f.write_str("<span class=\"")?;
+            Escaped(self.f).write_str(self.prefix)?;
+            write!(self.f, "-{}\">", class)?;
+            self.is_open = true;
+        }
+        Escaped(self.f).write_str(s)
+    }
+}
+
+/// Escapes the characters that have a special meaning in HTML text and attribute
+/// values.
+struct Escaped<'a, W: fmt::Write + ?Sized>(&'a mut W);
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Escaped<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for char in s.chars() {
+            match char {
+                '&' => self.0.write_str("&amp;")?,
+                '<' => self.0.write_str("&lt;")?,
+                '>' => self.0.write_str("&gt;")?,
+                '"' => self.0.write_str("&quot;")?,
+                '\'' => self.0.write_str("&#39;")?,
+                _ => self.0.write_char(char)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Like [`crate::fmt_align_numbers_with`] but renders every entry as HTML, see
+/// [`HtmlFormat`]. The [`AlignOptions::output_style`] is ignored.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{
+///     fmt_align_numbers_html, AlignOptions, FormatPrecision, FractionNumber, HtmlFormat,
+/// };
+///
+/// let html = fmt_align_numbers_html(
+///     &[FractionNumber::F64(-1.5), FractionNumber::I128(10)],
+///     FormatPrecision::Max(2),
+///     &AlignOptions::new(),
+///     &HtmlFormat::new(),
+/// );
+/// assert_eq!(
+///     html[0],
+///     "<span class=\"number\"><span class=\"number-whole\">\u{2212}1</span>\
+///      <span class=\"number-point\">.</span>\
+///      <span class=\"number-fraction\">5</span></span>"
+/// );
+/// ```
+pub fn fmt_align_numbers_html<T: AlignableNumber>(
+    numbers: &[T],
+    precision: FormatPrecision,
+    options: &AlignOptions,
+    format: &HtmlFormat,
+) -> Vec<String> {
+    // the padding of missing SI prefixes must not be collapsed by the browser either
+    let options = options.output_style(OutputStyle::Typographic);
+    let formatted = FormattedNumbers::new(numbers, precision, &options);
+    render_html(formatted.parts(), formatted.options(), format)
+}
+
+#[cfg(feature = "alloc")]
+/// Like [`crate::fmt_align_fraction_strings_with`] but renders every entry as HTML,
+/// see [`HtmlFormat`]. The [`AlignOptions::output_style`] is ignored.
+pub fn fmt_align_fraction_strings_html(
+    strings: &[&str],
+    options: &AlignOptions,
+    format: &HtmlFormat,
+) -> Vec<String> {
+    let decimal_separator = options.input_locale.decimal_separator;
+    render_html(
+        strings
+            .iter()
+            .map(|s| FractionParts::parse(s, decimal_separator)),
+        options,
+        format,
+    )
+}
+
+#[cfg(feature = "alloc")]
+fn render_html<'a>(
+    parts: impl Iterator<Item = FractionParts<'a>> + Clone,
+    options: &AlignOptions,
+    format: &HtmlFormat,
+) -> Vec<String> {
+    use alloc::string::ToString;
+
+    let layout = AlignmentLayout::from_parts(parts.clone(), options);
+    parts
+        .map(|p| HtmlEntry::new(layout, p, *format).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+#[cfg(feature = "alloc")]
+mod tests {
+
+    use super::*;
+    use crate::{Notation, OutputStyle};
+
+    #[test]
+    fn test_fmt_align_fraction_strings_html() {
+        let format = HtmlFormat::new().class_prefix("n");
+        let res = fmt_align_fraction_strings_html(&["-42", "0.25"], &AlignOptions::new(), &format);
+        assert_eq!(
+            vec![
+                "<span class=\"n\"><span class=\"n-whole\">\u{2212}42</span>\
+                 <span class=\"n-point\">\u{2008}</span>\
+                 <span class=\"n-fraction\">\u{2007}\u{2007}</span></span>",
+                "<span class=\"n\"><span class=\"n-whole\">\u{2007}\u{2007}0</span>\
+                 <span class=\"n-point\">.</span>\
+                 <span class=\"n-fraction\">25</span></span>",
+            ],
+            res
+        );
+
+        // the output style of the options is ignored, the content and the classes are
+        // escaped
+        let options = AlignOptions::new()
+            .output_style(OutputStyle::Plain)
+            .right_pad(false);
+        let format = HtmlFormat::new().class_prefix("a\"b");
+        let res = fmt_align_fraction_strings_html(&["<1>", "1"], &options, &format);
+        assert_eq!(
+            vec![
+                "<span class=\"a&quot;b\"><span class=\"a&quot;b-whole\">&lt;1&gt;</span></span>",
+                "<span class=\"a&quot;b\"><span class=\"a&quot;b-whole\">\u{2007}\u{2007}1</span></span>",
+            ],
+            res
+        );
+    }
+
+    #[test]
+    fn test_fmt_align_numbers_html() {
+        let options = AlignOptions::new().notation(Notation::Scientific);
+        let res = fmt_align_numbers_html(
+            &[1.5e-7, 20.0],
+            FormatPrecision::Max(2),
+            &options,
+            &HtmlFormat::new(),
+        );
+        assert_eq!(
+            vec![
+                "<span class=\"number\"><span class=\"number-whole\">1</span>\
+                 <span class=\"number-point\">.</span>\
+                 <span class=\"number-fraction\">5</span>\
+                 <span class=\"number-suffix\">e\u{2212}7</span></span>",
+                "<span class=\"number\"><span class=\"number-whole\">2</span>\
+                 <span class=\"number-point\">\u{2008}</span>\
+                 <span class=\"number-fraction\">\u{2007}</span>\
+                 <span class=\"number-suffix\">e1\u{2007}</span></span>",
+            ],
+            res
+        );
+
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "Ω" });
+        let res = fmt_align_numbers_html(
+            &[4.7, 47000.0],
+            FormatPrecision::Max(1),
+            &options,
+            &HtmlFormat::new(),
+        );
+        assert_eq!(
+            vec![
+                "<span class=\"number\"><span class=\"number-whole\">\u{2007}4</span>\
+                 <span class=\"number-point\">.</span>\
+                 <span class=\"number-fraction\">7</span>\
+                 <span class=\"number-suffix\"> \u{2007}Ω</span></span>",
+                "<span class=\"number\"><span class=\"number-whole\">47</span>\
+                 <span class=\"number-point\">\u{2008}</span>\
+                 <span class=\"number-fraction\">\u{2007}</span>\
+                 <span class=\"number-suffix\"> kΩ</span></span>",
+            ],
+            res
+        );
+    }
+}
@@ -0,0 +1,363 @@
+//! LaTeX rendering of aligned numbers for `tabular` environments, see [`LatexMode`].
+
+use crate::align::{write_to_string, AlignmentLayout, Segment};
+use crate::number::FormattedNumbers;
+use crate::options::{FIGURE_SPACE, MINUS_SIGN, PUNCTUATION_SPACE};
+use crate::parts::FractionParts;
+use crate::{AlignOptions, AlignableNumber, FormatPrecision, Locale, Notation, OutputStyle};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// How [`fmt_align_numbers_latex`] aligns the numbers of a LaTeX column.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LatexMode {
+    /// An `S` column of the `siunitx` package, e.g. `S[table-format=-4.2]`. The
+    /// `table-format` is computed from the widest sign, whole part, fractional part
+    /// and exponent, and the cells contain the plain numbers, which are aligned by
+    /// `siunitx`. Non-finite values are wrapped in braces, so that `siunitx` renders
+    /// them as text. [`Notation::SiPrefix`] is replaced by [`Notation::Engineering`],
+    /// as `siunitx` can't parse units in `S` columns.
+    Siunitx,
+    /// An `r` column in which every cell is padded with `\phantom{0}` for missing
+    /// digits and with `\phantom` of the separator for a missing decimal separator or
+    /// grouping separator. A missing SI prefix is padded with `\phantom` of the widest
+    /// prefix of the column. Needs no package and works with all fonts with tabular
+    /// figures, which is the default in LaTeX. Negative numbers get `\textminus`.
+    Phantom,
+}
+
+/// A column of aligned numbers for a LaTeX `tabular` environment, see
+/// [`fmt_align_numbers_latex`].
+///
+/// For tables with multiple columns, join the [`Self::spec`]s of all columns and the
+/// cells of every row with ` & `.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatexColumn {
+    spec: String,
+    cells: Vec<String>,
+}
+
+impl LatexColumn {
+    /// Returns the column specification, e.g. `S[table-format=-4.2]` or `r`.
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    /// Returns the content of the cells, escaped for LaTeX.
+    pub fn cells(&self) -> &[String] {
+        &self.cells
+    }
+
+    /// Returns the rows of a `tabular` environment with only this column, i.e. every
+    /// cell followed by ` \\`.
+    pub fn rows(&self) -> Vec<String> {
+        self.cells
+            .iter()
+            .map(|cell| format!("{} \\\\", cell))
+            .collect()
+    }
+
+    /// Returns a complete `tabular` environment with only this column.
+    pub fn tabular(&self) -> String {
+        let rows = self
+            .rows()
+            .iter()
+            .map(|row| format!("{}\n", row))
+            .collect::<String>();
+        format!(
+            "\\begin{{tabular}}{{{}}}\n{}\\end{{tabular}}",
+            self.spec, rows
+        )
+    }
+}
+
+/// Like [`crate::fmt_align_numbers_with`] but renders the numbers as a column of a
+/// LaTeX `tabular` environment, see [`LatexMode`]. The
+/// [`AlignOptions::output_style`] is ignored.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{
+///     fmt_align_numbers_latex, AlignOptions, FormatPrecision, FractionNumber, LatexMode,
+/// };
+///
+/// let numbers = [FractionNumber::F64(-42.0), FractionNumber::F64(0.3214), FractionNumber::I128(1000)];
+/// let column = fmt_align_numbers_latex(
+///     &numbers,
+///     FormatPrecision::Max(4),
+///     &AlignOptions::new(),
+///     LatexMode::Siunitx,
+/// );
+/// assert_eq!(column.spec(), "S[table-format=-4.4]");
+/// assert_eq!(column.cells(), ["-42", "0.3214", "1000"]);
+///
+/// let column = fmt_align_numbers_latex(
+///     &numbers,
+///     FormatPrecision::Max(4),
+///     &AlignOptions::new(),
+///     LatexMode::Phantom,
+/// );
+/// assert_eq!(column.spec(), "r");
+/// assert_eq!(
+///     column.cells()[0],
+///     "\\phantom{0}\\textminus{}42\\phantom{.}\\phantom{0}\\phantom{0}\\phantom{0}\\phantom{0}"
+/// );
+/// ```
+pub fn fmt_align_numbers_latex<T: AlignableNumber>(
+    numbers: &[T],
+    precision: FormatPrecision,
+    options: &AlignOptions,
+    mode: LatexMode,
+) -> LatexColumn {
+    match mode {
+        LatexMode::Siunitx => {
+            let options = match options.notation {
+                Notation::SiPrefix { .. } => options.notation(Notation::Engineering),
+                _ => *options,
+            };
+            let formatted = FormattedNumbers::new(numbers, precision, &options);
+            let cells = formatted
+                .parts()
+                .map(|p| siunitx_cell(p, formatted.options()))
+                .collect::<Vec<_>>();
+            LatexColumn {
+                spec: format!("S[table-format={}]", siunitx_table_format(&cells)),
+                cells,
+            }
+        }
+        LatexMode::Phantom => {
+            let options = options.output_style(OutputStyle::Typographic);
+            let formatted = FormattedNumbers::new(numbers, precision, &options);
+            let layout = AlignmentLayout::from_parts(formatted.parts(), formatted.options());
+            let prefix = match options.notation {
+                Notation::SiPrefix { unit } => widest_si_prefix(formatted.parts(), unit),
+                _ => "0",
+            };
+            let cells = formatted
+                .parts()
+                .map(|p| {
+                    write_to_string(|cell| {
+                        let mut out = PhantomWriter {
+                            out: cell,
+                            options: formatted.options(),
+                            segment: Segment::Whole,
+                            prefix,
+                        };
+                        layout.write_segments(&mut out, p, |out, segment| {
+                            out.segment = segment;
+                            Ok(())
+                        })
+                    })
+                })
+                .collect();
+            LatexColumn {
+                spec: "r".to_string(),
+                cells,
+            }
+        }
+    }
+}
+
+/// Renders a number without padding and grouping separators, with `.` as decimal
+/// separator, which is what `siunitx` parses.
+fn siunitx_cell(parts: FractionParts, options: &AlignOptions) -> String {
+    let options = options
+        .output_locale(Locale::POSIX)
+        .output_style(OutputStyle::Plain)
+        .right_pad(false)
+        .zero_pad_fraction(false)
+        .min_width(0);
+    let layout = AlignmentLayout::from_parts(core::iter::once(parts), &options);
+    let cell = layout.display_parts(parts).to_string();
+    if parts.is_finite() {
+        cell
+    } else {
+        format!("{{{}}}", escape_latex(&cell))
+    }
+}
+
+/// Returns the `table-format` of `siunitx` that fits all cells, e.g. `-4.2` or
+/// `1.3e-2`.
+fn siunitx_table_format(cells: &[String]) -> String {
+    let mut has_sign = false;
+    let mut whole_digits = 1;
+    let mut fraction_digits = 0;
+    let mut exponent: Option<(bool, usize)> = None;
+    for cell in cells.iter().filter(|cell| !cell.starts_with('{')) {
+        let parts = FractionParts::parse(cell, '.');
+        has_sign |= !parts.sign.is_empty();
+        whole_digits = whole_digits.max(parts.whole.len());
+        fraction_digits = fraction_digits.max(parts.fraction.map_or(0, str::len));
+        if let Some(suffix) = parts.suffix {
+            let (exponent_sign, digits) =
+                suffix[1..].split_at(suffix[1..].find(|c: char| c.is_ascii_digit()).unwrap_or(0));
+            let (has_exponent_sign, exponent_digits) = exponent.unwrap_or((false, 1));
+            exponent = Some((
+                has_exponent_sign || !exponent_sign.is_empty(),
+                exponent_digits.max(digits.len()),
+            ));
+        }
+    }
+
+    let mut table_format = format!("{}{}", if has_sign { "-" } else { "" }, whole_digits);
+    if fraction_digits > 0 {
+        table_format += &format!(".{}", fraction_digits);
+    }
+    if let Some((has_exponent_sign, exponent_digits)) = exponent {
+        let sign = if has_exponent_sign { "-" } else { "" };
+        table_format += &format!("e{}{}", sign, exponent_digits);
+    }
+    table_format
+}
+
+/// The SI prefixes from the widest to the narrowest glyph in common fonts.
+const SI_PREFIXES_BY_WIDTH: [&str; 8] = ["M", "m", "G", "T", "k", "n", "µ", "p"];
+
+/// Returns the widest SI prefix of the numbers, which are formatted in
+/// [`Notation::SiPrefix`] with the unit, or `0` if no number has a prefix.
+fn widest_si_prefix<'a>(
+    parts: impl Iterator<Item = FractionParts<'a>>,
+    unit: &str,
+) -> &'static str {
+    let prefixes = parts
+        .filter_map(|p| p.suffix?.strip_prefix(' ')?.strip_suffix(unit))
+        .collect::<Vec<_>>();
+    SI_PREFIXES_BY_WIDTH
+        .iter()
+        .find(|prefix| prefixes.contains(prefix))
+        .copied()
+        .unwrap_or("0")
+}
+
+/// Replaces the typographic padding of an entry by `\phantom`s and escapes all
+/// other characters.
+struct PhantomWriter<'a> {
+    out: &'a mut String,
+    options: &'a AlignOptions,
+    segment: Segment,
+    /// The content of the `\phantom` of the padding of a missing SI prefix.
+    prefix: &'a str,
+}
+
+impl Write for PhantomWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let locale = &self.options.output_locale;
+        for char in s.chars() {
+            match char {
+                FIGURE_SPACE => match self.segment {
+                    // a missing SI prefix, or missing exponent digits with `0`
+                    Segment::Suffix => write!(self.out, "\\phantom{{{}}}", self.prefix)?,
+                    _ => self.out.push_str("\\phantom{0}"),
+                },
+                PUNCTUATION_SPACE => {
+                    let separator = match self.segment {
+                        Segment::Whole => locale.grouping_separator().unwrap_or('.'),
+                        _ => locale.decimal_separator,
+                    };
+                    self.out
+                        .push_str(&format!("\\phantom{{{}}}", escape_latex_char(separator)));
+                }
+                MINUS_SIGN => self.out.push_str("\\textminus{}"),
+                _ => self.out.push_str(&escape_latex_char(char)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes the characters that have a special meaning in LaTeX.
+fn escape_latex(text: &str) -> String {
+    text.chars().map(escape_latex_char).collect()
+}
+
+fn escape_latex_char(char: char) -> String {
+    match char {
+        '\\' => "\\textbackslash{}".to_string(),
+        '^' => "\\textasciicircum{}".to_string(),
+        '~' => "\\textasciitilde{}".to_string(),
+        '{' | '}' | '$' | '&' | '#' | '_' | '%' => format!("\\{}", char),
+        _ => char.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FractionNumber, SignMode};
+
+    #[test]
+    fn test_fmt_align_numbers_latex_siunitx() {
+        let options = AlignOptions::new().grouping_separator(Some(','));
+        let column = fmt_align_numbers_latex(
+            &[1234.5, 0.25, f64::NAN],
+            FormatPrecision::Max(2),
+            &options,
+            LatexMode::Siunitx,
+        );
+        assert_eq!("S[table-format=4.2]", column.spec());
+        assert_eq!(["1234.5", "0.25", "{NaN}"], column.cells());
+        assert_eq!(
+            "\\begin{tabular}{S[table-format=4.2]}\n1234.5 \\\\\n0.25 \\\\\n{NaN} \\\\\n\\end{tabular}",
+            column.tabular()
+        );
+
+        let options = AlignOptions::new()
+            .notation(Notation::SiPrefix { unit: "F" })
+            .sign_mode(SignMode::Always);
+        let column = fmt_align_numbers_latex(
+            &[FractionNumber::F64(3.3e-9), FractionNumber::F64(47e3)],
+            FormatPrecision::Max(1),
+            &options,
+            LatexMode::Siunitx,
+        );
+        assert_eq!("S[table-format=-2.1e-1]", column.spec());
+        assert_eq!(["+3.3e-9", "+47e3"], column.cells());
+    }
+
+    #[test]
+    fn test_fmt_align_numbers_latex_phantom() {
+        let options = AlignOptions::new()
+            .output_locale(Locale::DE)
+            .right_pad(false);
+        let column = fmt_align_numbers_latex(
+            &[1000.5, -2.0],
+            FormatPrecision::Max(1),
+            &options,
+            LatexMode::Phantom,
+        );
+        assert_eq!("r", column.spec());
+        assert_eq!(
+            [
+                "1.000,5",
+                "\\phantom{0}\\phantom{.}\\phantom{0}\\textminus{}2",
+            ],
+            column.cells()
+        );
+
+        let column = fmt_align_numbers_latex(
+            &[FractionNumber::F64(0.5), FractionNumber::F64(f64::INFINITY)],
+            FormatPrecision::Max(1),
+            &AlignOptions::new().notation(Notation::SiPrefix { unit: "%" }),
+            LatexMode::Phantom,
+        );
+        assert_eq!(["500 m\\%", "inf \\phantom{m}\\%"], column.cells());
+
+        let column = fmt_align_numbers_latex(
+            &[4.7e3, 2.2e6, 1.0],
+            FormatPrecision::Max(1),
+            &AlignOptions::new().notation(Notation::SiPrefix { unit: "Ω" }),
+            LatexMode::Phantom,
+        );
+        assert_eq!(
+            [
+                "4.7 kΩ",
+                "2.2 MΩ",
+                "1\\phantom{.}\\phantom{0} \\phantom{M}Ω"
+            ],
+            column.cells()
+        );
+    }
+}
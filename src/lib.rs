@@ -40,6 +40,18 @@ SOFTWARE.
 //! " 1000     "
 //! "-1000.2   "
 //! "    2     "
+//! ```
+//!
+//! ## Display width
+//! All widths are terminal display widths, so that `−` (U+2212), fullwidth digits,
+//! CJK characters, combining marks and emoji in numbers, units and table cells are
+//! padded correctly. The crate has no dependencies and uses compact built-in tables,
+//! which approximate the East Asian Width property (UAX #11) and grapheme clusters
+//! (UAX #29) for the characters that commonly appear next to numbers. Characters
+//! outside of these tables count as one column. Known gaps are, for example,
+//! combining marks of most Indic scripts other than Devanagari (e.g. U+0A3C), rare
+//! wide characters (e.g. U+16FF0) and keycap sequences such as `1️⃣`, which count
+//! as one column instead of two.
 
 #![deny(
     clippy::all,
@@ -56,17 +68,85 @@ SOFTWARE.
     clippy::redundant_pub_crate,
     clippy::suboptimal_flops
 )]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs)]
 #![deny(missing_debug_implementations)]
 #![deny(rustdoc::all)]
 
-/// Abstraction over floating point types [`f32`] and [`f64`].
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod align;
+#[cfg(feature = "alloc")]
+mod csv;
+mod error;
+mod fixed;
+mod html;
+#[cfg(feature = "alloc")]
+mod latex;
+mod locale;
+#[cfg(feature = "alloc")]
+mod notation;
+mod number;
+mod options;
+mod parts;
+#[cfg(feature = "alloc")]
+mod stream;
+#[cfg(feature = "alloc")]
+mod table;
+mod width;
+mod write;
+
+pub use align::{AlignedEntry, AlignmentLayout, NumberEntry};
+#[cfg(feature = "alloc")]
+pub use csv::{CsvAligner, CsvOutput};
+#[cfg(feature = "alloc")]
+pub use error::{AlignError, CsvError};
+pub use error::{FixedAlignError, InvalidNumberReason};
+pub use fixed::{align_into, FixedString};
+#[cfg(feature = "alloc")]
+pub use html::{fmt_align_fraction_strings_html, fmt_align_numbers_html};
+pub use html::{HtmlEntry, HtmlFormat};
+#[cfg(feature = "alloc")]
+pub use latex::{fmt_align_numbers_latex, LatexColumn, LatexMode};
+pub use locale::{Grouping, Locale};
+pub use number::AlignableNumber;
+pub use options::{AlignOptions, Notation, OutputStyle, Overflow, SignMode};
+#[cfg(feature = "alloc")]
+pub use stream::{StreamedLine, StreamingAligner};
+#[cfg(feature = "alloc")]
+pub use table::{Cell, Column, Table, TextAlign};
+pub use width::display_width;
+#[cfg(feature = "alloc")]
+pub use write::write_aligned_fmt;
+#[cfg(feature = "std")]
+pub use write::{write_aligned, write_aligned_strings};
+pub use write::{write_aligned_strings_fmt, LineFormat};
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+#[cfg(feature = "alloc")]
+use parts::FractionParts;
+
+/// Abstraction over floating point types [`f32`] and [`f64`] and all primitive
+/// integer types. Integers are formatted exactly, also if they exceed the precision
+/// of floating point types.
 #[derive(Debug, Copy, Clone)]
 pub enum FractionNumber {
     /// Variant for [`f32`].
     F32(f32),
     /// Variant for [`f64`].
     F64(f64),
+    /// Variant for all signed integer types, i.e. [`i8`], [`i16`], [`i32`], [`i64`],
+    /// [`i128`], and [`isize`].
+    I128(i128),
+    /// Variant for all unsigned integer types, i.e. [`u8`], [`u16`], [`u32`], [`u64`],
+    /// [`u128`], and [`usize`].
+    U128(u128),
 }
 
 impl From<f32> for FractionNumber {
@@ -81,23 +161,56 @@ impl From<f64> for FractionNumber {
     }
 }
 
-impl FractionNumber {
-    fn format(self, precision: FormatPrecision) -> String {
-        match self {
-            Self::F32(val) => {
-                format!("{val:.precision$}", val = val, precision = precision.val())
-            }
-            Self::F64(val) => {
-                format!("{val:.precision$}", val = val, precision = precision.val())
+macro_rules! impl_from_integer {
+    ($variant:ident, $wide:ty, $($ty:ty),+) => {
+        $(
+            impl From<$ty> for FractionNumber {
+                fn from(val: $ty) -> Self {
+                    Self::$variant(val as $wide)
+                }
             }
+        )+
+    };
+}
+
+impl_from_integer!(I128, i128, i8, i16, i32, i64, i128, isize);
+impl_from_integer!(U128, u128, u8, u16, u32, u64, u128, usize);
+
+impl AlignableNumber for FractionNumber {
+    fn is_negative(&self) -> bool {
+        match self {
+            Self::F32(val) => val.is_negative(),
+            Self::F64(val) => val.is_negative(),
+            Self::I128(val) => val.is_negative(),
+            Self::U128(val) => val.is_negative(),
+        }
+    }
+
+    fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Self::F32(val) => val.write_fixed(precision, out),
+            Self::F64(val) => val.write_fixed(precision, out),
+            Self::I128(val) => val.write_fixed(precision, out),
+            Self::U128(val) => val.write_fixed(precision, out),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write_scientific(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Self::F32(val) => val.write_scientific(precision, out),
+            Self::F64(val) => val.write_scientific(precision, out),
+            Self::I128(val) => val.write_scientific(precision, out),
+            Self::U128(val) => val.write_scientific(precision, out),
         }
     }
 }
 
 /// The precision of decimal places for [`fmt_align_fractions`].
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum FormatPrecision {
-    /// Format with exactly `n` decimal places.
+    /// Format with exactly `n` decimal places. Zeroes are kept, i.e. `2.5` becomes
+    /// `2.500` with `Exact(3)`.
     Exact(u8),
     /// Format with a maximum of `n` decimal places. Might happen that there is not a
     /// single decimal place required.
@@ -114,28 +227,69 @@ impl FormatPrecision {
     }
 }
 
+#[cfg(feature = "alloc")]
 /// Convenient wrapper around [`fmt_align_fraction_strings`] that takes
 /// a slice of floating point values, formats them all with a maximum
 /// precision and returns a list of aligned, formatted strings.
-/// * `precision`: See [`FormatPrecision`].
+/// * `precision`: See [`FormatPrecision`]. With [`FormatPrecision::Exact`], unnecessary
+///   zeroes are kept (see [`fmt_align_fraction_strings_zero_padded`]).
 pub fn fmt_align_fractions(
     fractions: &[FractionNumber],
     precision: FormatPrecision,
 ) -> Vec<String> {
-    let fraction_strings = fractions
-        .iter()
-        .map(|fr| fr.format(precision))
-        .collect::<Vec<String>>();
+    fmt_align_fractions_with(fractions, precision, &AlignOptions::new())
+}
 
-    let str_vec = fraction_strings
-        .iter()
-        .map(|s| s.as_str())
-        .collect::<Vec<&str>>();
+#[cfg(feature = "alloc")]
+/// Like [`fmt_align_fractions`] but with custom [`AlignOptions`].
+///
+/// With [`FormatPrecision::Exact`], unnecessary zeroes are always kept, regardless of
+/// [`AlignOptions::strip_trailing_zeroes`].
+pub fn fmt_align_fractions_with(
+    fractions: &[FractionNumber],
+    precision: FormatPrecision,
+    options: &AlignOptions,
+) -> Vec<String> {
+    fmt_align_numbers_with(fractions, precision, options)
+}
 
-    fmt_align_fraction_strings(&str_vec)
+#[cfg(feature = "alloc")]
+/// Like [`fmt_align_fractions`] but for all types that implement [`AlignableNumber`],
+/// such as the primitive number types or your own numeric types.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{fmt_align_numbers, FormatPrecision};
+///
+/// let aligned = fmt_align_numbers(&[-42.0, 0.3214, 1000.0], FormatPrecision::Max(4));
+/// assert_eq!(aligned, [" -42     ", "   0.3214", "1000     "]);
+/// ```
+pub fn fmt_align_numbers<T: AlignableNumber>(
+    numbers: &[T],
+    precision: FormatPrecision,
+) -> Vec<String> {
+    fmt_align_numbers_with(numbers, precision, &AlignOptions::new())
+}
+
+#[cfg(feature = "alloc")]
+/// Like [`fmt_align_numbers`] but with custom [`AlignOptions`].
+///
+/// With [`FormatPrecision::Exact`], unnecessary zeroes are always kept, regardless of
+/// [`AlignOptions::strip_trailing_zeroes`].
+pub fn fmt_align_numbers_with<T: AlignableNumber>(
+    numbers: &[T],
+    precision: FormatPrecision,
+    options: &AlignOptions,
+) -> Vec<String> {
+    let formatted = number::FormattedNumbers::new(numbers, precision, options);
+    let parts = formatted.parts().collect::<Vec<_>>();
+    align::align_parts(&parts, formatted.options())
 }
 
-/// Aligns a number of formatted fraction numbers. Valid strings are for example
+#[cfg(feature = "alloc")]
+/// Aligns a number of formatted fraction numbers.
+///
+/// Valid strings are for example
 /// `1`, `3.14`, and `-42`. Aligns all with additional padding on the left so that
 /// all of them can be printed line by line in an aligned way. This means that
 /// in every line the tens digits will be aligned, the once places will be aligned,
@@ -160,116 +314,102 @@ pub fn fmt_align_fractions(
 /// "-1000.2   "
 /// "    2     "
 /// ```
+///
+/// The strings are not validated. Use [`try_fmt_align_fraction_strings`] to reject
+/// malformed input such as `1.2.3` or `abc`.
 pub fn fmt_align_fraction_strings(strings: &[&str]) -> Vec<String> {
-    // normalize all fractional parts
-    let strings = strings
-        .iter()
-        .map(|x| normalize_fraction_part(x))
-        .collect::<Vec<&str>>();
-
-    let max = strings
-        .iter()
-        .map(|x| get_whole_part(x))
-        .map(|x| x.len())
-        .max()
-        .unwrap();
-
-    // create n new strings
-    let mut new_strings = vec![String::new(); strings.len()];
-    strings.iter().enumerate().for_each(|(index, string)| {
-        let whole_part = get_whole_part(string);
-        let spaces = max - whole_part.len();
-        new_strings[index].push_str(&" ".repeat(spaces));
-        new_strings[index].push_str(string);
-    });
-
-    // now add spaces in the end so that all are exactly same aligned, on left
-    // as well as right; technically this is not really needed, but it may
-    // help in some situations. Also this can be easily revoked with a right trim.
-    let max = new_strings.iter().map(|s| s.len()).max().unwrap();
-    for string in &mut new_strings {
-        let spaces = max - string.len();
-        string.push_str(&" ".repeat(spaces))
-    }
-
-    new_strings
+    fmt_align_fraction_strings_with(strings, &AlignOptions::new())
 }
 
-/// Get the whole part (TODO is this the right term?)
-/// from a formatted fraction number string.
-/// * `123` => `123`
-/// * `123.13` => `123`
-/// * `0.1234` => `0`
-/// * `-10.1234` => `-10`
-fn get_whole_part(string: &str) -> &str {
-    // if it doesn't contain "." the whole thing is returned
-    string.split('.').next().unwrap()
+#[cfg(feature = "alloc")]
+/// Like [`fmt_align_fraction_strings`] but with custom [`AlignOptions`].
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{fmt_align_fraction_strings_with, AlignOptions, SignMode};
+///
+/// let options = AlignOptions::new().sign_mode(SignMode::Always).min_width(10);
+/// let aligned = fmt_align_fraction_strings_with(&["-42", "0.3214"], &options);
+/// assert_eq!(aligned, ["  -42     ", "   +0.3214"]);
+/// ```
+pub fn fmt_align_fraction_strings_with(strings: &[&str], options: &AlignOptions) -> Vec<String> {
+    let parts = strings
+        .iter()
+        .map(|s| FractionParts::parse(s, options.input_locale.decimal_separator))
+        .collect::<Vec<_>>();
+    align::align_parts(&parts, options)
 }
 
-/// Get the fractional part from a formatted fraction number string.
-/// * `123` => `None`
-/// * `123.13` => `Some(13)`
-/// * `0.1234` => `Some(1234)`
-/// * `-10.1234` => `Some(1234)`
-fn get_fractional_part(string: &str) -> Option<&str> {
-    let mut split = string.split('.');
-    let _whole_part = split.next().unwrap();
-    split.next()
+#[cfg(feature = "alloc")]
+/// Like [`fmt_align_fraction_strings`] but keeps all zeroes of the fractional parts and
+/// pads every entry with zeroes to the widest fractional part instead of spaces.
+///
+/// This is useful if all values should be printed with a constant precision.
+///
+/// ## Example Input
+/// ```text
+/// "-42"
+/// "2.500"
+/// "-1000.2"
+/// ```
+/// ## Example Output
+/// ```text
+/// "  -42.000"
+/// "    2.500"
+/// "-1000.200"
+/// ```
+pub fn fmt_align_fraction_strings_zero_padded(strings: &[&str]) -> Vec<String> {
+    let options = AlignOptions::new()
+        .strip_trailing_zeroes(false)
+        .zero_pad_fraction(true);
+    fmt_align_fraction_strings_with(strings, &options)
 }
 
-/// Consumes the whole number string and normalizes
-/// (if present) the fraction part. This means:
-/// * `123` => `123`
-/// * `123.13` => `123.13`
-/// * `0.1234000` => `0.1234`
-/// * `-10.000000` => `-10`
-fn normalize_fraction_part(string: &str) -> &str {
-    let whole_part = get_whole_part(string);
-    let fraction_part = get_fractional_part(string);
-    if fraction_part.is_none() {
-        return whole_part;
-    }
-    let fraction_part = fraction_part.unwrap();
-    let zeroes = fraction_part_count_zeroes(fraction_part);
-    if fraction_part.len() == zeroes {
-        whole_part
-    } else {
-        &string[0..string.len() - zeroes]
-    }
+#[cfg(feature = "alloc")]
+/// Like [`fmt_align_fraction_strings`] but validates every string first.
+///
+/// Valid strings consist of an optional leading sign (`-` or `+`), digits and at
+/// most one decimal point, e.g. `1`, `-3.14`, `+.5` or `42.`, optionally followed by
+/// an exponent such as in `1.5e-7` or `6.02E23`. Additionally, the
+/// non-finite values `NaN`, `inf`, `-inf` and `+inf` are accepted, as they are
+/// produced by formatting floating point values. An empty slice results in an
+/// empty list.
+///
+/// Returns [`AlignError::InvalidNumber`] for the first entry that is not valid.
+pub fn try_fmt_align_fraction_strings(strings: &[&str]) -> Result<Vec<String>, AlignError> {
+    try_fmt_align_fraction_strings_with(strings, &AlignOptions::new())
 }
 
-/// Takes only the fraction part of a string without ".".
-/// Counts that in "123000" (fractional part of "0.123000") are three unnecessary zeroes.
-/// In "0.0000" there are four unnecessary zeroes.
-fn fraction_part_count_zeroes(fraction_part: &str) -> usize {
-    let mut zeroes = 0;
-    let chars = fraction_part.chars().collect::<Vec<char>>();
-    for i in 0..fraction_part.len() {
-        // go backwards
-        let i = fraction_part.len() - 1 - i;
-        let char = chars[i];
-        if char == '0' {
-            zeroes += 1;
-        } else {
-            break;
-        }
-    }
-    zeroes
+#[cfg(feature = "alloc")]
+/// Like [`try_fmt_align_fraction_strings`] but with custom [`AlignOptions`].
+///
+/// The strings are validated with the decimal and grouping separators of
+/// [`AlignOptions::input_locale`]. Grouping separators are valid in the whole part only.
+pub fn try_fmt_align_fraction_strings_with(
+    strings: &[&str],
+    options: &AlignOptions,
+) -> Result<Vec<String>, AlignError> {
+    let parts = strings
+        .iter()
+        .enumerate()
+        .map(|(index, string)| {
+            FractionParts::try_parse(string, &options.input_locale).map_err(|reason| {
+                AlignError::InvalidNumber {
+                    index,
+                    input: (*string).to_string(),
+                    reason,
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(align::align_parts(&parts, options))
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_fraction_part_count_zeroes() {
-        assert_eq!(3, fraction_part_count_zeroes("123000"));
-        assert_eq!(0, fraction_part_count_zeroes("123"));
-        assert_eq!(1, fraction_part_count_zeroes("0"));
-        assert_eq!(11, fraction_part_count_zeroes("00000012800000000000"));
-    }
-
     #[test]
     fn test_fmt_align_fraction_strings() {
         let res = fmt_align_fraction_strings(
@@ -309,6 +449,648 @@ mod tests {
         assert_eq!("1", res[1]);
     }
 
+    #[test]
+    fn test_fmt_empty_list() {
+        assert!(fmt_align_fraction_strings(&[]).is_empty());
+        assert_eq!(Ok(vec![]), try_fmt_align_fraction_strings(&[]));
+        assert!(fmt_align_fractions(&[], FormatPrecision::Max(2)).is_empty());
+    }
+
+    #[test]
+    fn test_try_fmt_align_fraction_strings() {
+        let res = try_fmt_align_fraction_strings(&["-42", "+0.3214", "1000.", "NaN"]).unwrap();
+        assert_eq!(" -42     ", res[0]);
+        assert_eq!("  +0.3214", res[1]);
+        assert_eq!("1000     ", res[2]);
+        assert_eq!(" NaN     ", res[3]);
+
+        let err = try_fmt_align_fraction_strings(&["1", "1.2.3"]).unwrap_err();
+        assert_eq!(
+            AlignError::InvalidNumber {
+                index: 1,
+                input: "1.2.3".to_string(),
+                reason: InvalidNumberReason::MultipleDecimalPoints,
+            },
+            err
+        );
+    }
+
+    #[test]
+    fn test_fmt_exact_precision_keeps_zeroes() {
+        let res = fmt_align_fractions(
+            &[
+                FractionNumber::F64(2.5),
+                FractionNumber::F64(1.0),
+                FractionNumber::F32(-10.0),
+                FractionNumber::F64(f64::NAN),
+            ],
+            FormatPrecision::Exact(3),
+        );
+        assert_eq!("  2.500", res[0]);
+        assert_eq!("  1.000", res[1]);
+        assert_eq!("-10.000", res[2]);
+        assert_eq!("NaN    ", res[3]);
+
+        let res = fmt_align_fractions(&[FractionNumber::F64(2.5)], FormatPrecision::Exact(0));
+        assert_eq!("2", res[0]);
+    }
+
+    #[test]
+    fn test_fmt_align_fraction_strings_zero_padded() {
+        let res = fmt_align_fraction_strings_zero_padded(&["-42", "2.500", "-1000.2", "7."]);
+        assert_eq!("  -42.000", res[0]);
+        assert_eq!("    2.500", res[1]);
+        assert_eq!("-1000.200", res[2]);
+        assert_eq!("    7.000", res[3]);
+
+        let res = fmt_align_fraction_strings_zero_padded(&["1", "20"]);
+        assert_eq!(" 1", res[0]);
+        assert_eq!("20", res[1]);
+    }
+
+    #[test]
+    fn test_fmt_align_with_options() {
+        let options = AlignOptions::new()
+            .padding_char('*')
+            .strip_trailing_zeroes(false);
+        let res = fmt_align_fraction_strings_with(&["-42", "2.500"], &options);
+        assert_eq!("-42****", res[0]);
+        assert_eq!("**2.500", res[1]);
+
+        let options = AlignOptions::new()
+            .right_pad(false)
+            .sign_mode(SignMode::NegativeOnly)
+            .min_width(6);
+        let res = fmt_align_fraction_strings_with(&["+1.5", "-10"], &options);
+        assert_eq!("   1.5", res[0]);
+        assert_eq!(" -10", res[1]);
+
+        let options = AlignOptions::new().decimal_separator(',');
+        let res = fmt_align_fractions_with(
+            &[FractionNumber::F64(-1.25), FractionNumber::F32(10.0)],
+            FormatPrecision::Max(3),
+            &options,
+        );
+        assert_eq!("-1,25", res[0]);
+        assert_eq!("10   ", res[1]);
+    }
+
+    #[test]
+    fn test_fmt_scientific_notation() {
+        let res = fmt_align_fraction_strings(&["1.5e-7", "-6.020E23", "12.25", "1e5"]);
+        assert_eq!(" 1.5 e-7", res[0]);
+        assert_eq!("-6.02E23", res[1]);
+        assert_eq!("12.25   ", res[2]);
+        assert_eq!(" 1   e5 ", res[3]);
+
+        let options = AlignOptions::new().notation(Notation::Scientific);
+        let res = fmt_align_fractions_with(
+            &[
+                FractionNumber::F64(0.000_000_15),
+                FractionNumber::F32(-1000.0),
+            ],
+            FormatPrecision::Max(3),
+            &options,
+        );
+        assert_eq!(" 1.5e-7", res[0]);
+        assert_eq!("-1  e3 ", res[1]);
+
+        let res = fmt_align_fractions_with(
+            &[FractionNumber::F64(1.5), FractionNumber::F64(6.02e23)],
+            FormatPrecision::Exact(2),
+            &options.notation(Notation::Auto),
+        );
+        assert_eq!("1.50   ", res[0]);
+        assert_eq!("6.02e23", res[1]);
+    }
+
+    #[test]
+    fn test_fmt_engineering_notation() {
+        let options = AlignOptions::new().notation(Notation::Engineering);
+        let res = fmt_align_fractions_with(
+            &[
+                FractionNumber::F64(47000.0),
+                FractionNumber::F64(0.000_000_003_3),
+                FractionNumber::F64(-1_200_000.0),
+                FractionNumber::F64(f64::NAN),
+            ],
+            FormatPrecision::Max(3),
+            &options,
+        );
+        assert_eq!(" 47  e3 ", res[0]);
+        assert_eq!("  3.3e-9", res[1]);
+        assert_eq!(" -1.2e6 ", res[2]);
+        assert_eq!("NaN     ", res[3]);
+    }
+
+    #[test]
+    fn test_fmt_si_prefixes() {
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "Ω" });
+        let res = fmt_align_fractions_with(
+            &[
+                FractionNumber::F64(47000.0),
+                FractionNumber::F64(4.7),
+                FractionNumber::F64(1_200_000.0),
+                FractionNumber::F64(0.000_22),
+            ],
+            FormatPrecision::Max(3),
+            &options,
+        );
+        assert_eq!(" 47   kΩ", res[0]);
+        assert_eq!("  4.7  Ω", res[1]);
+        assert_eq!("  1.2 MΩ", res[2]);
+        assert_eq!("220   µΩ", res[3]);
+
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "" });
+        let res = fmt_align_fractions_with(
+            &[FractionNumber::F64(1.5), FractionNumber::F64(20.0)],
+            FormatPrecision::Max(3),
+            &options,
+        );
+        assert_eq!(" 1.5", res[0]);
+        assert_eq!("20  ", res[1]);
+    }
+
+    #[test]
+    fn test_fmt_integers() {
+        let res = fmt_align_fractions(
+            &[
+                FractionNumber::from(u64::MAX),
+                FractionNumber::from(-42_i8),
+                FractionNumber::from(0.5_f64),
+                FractionNumber::from(1024_usize),
+            ],
+            FormatPrecision::Max(2),
+        );
+        assert_eq!("18446744073709551615  ", res[0]);
+        assert_eq!("                 -42  ", res[1]);
+        assert_eq!("                   0.5", res[2]);
+        assert_eq!("                1024  ", res[3]);
+
+        let res = fmt_align_fractions(
+            &[FractionNumber::from(7_u8), FractionNumber::from(2.5_f32)],
+            FormatPrecision::Exact(2),
+        );
+        assert_eq!("7.00", res[0]);
+        assert_eq!("2.50", res[1]);
+
+        let options = AlignOptions::new().notation(Notation::Scientific);
+        let res = fmt_align_fractions_with(
+            &[FractionNumber::from(i128::MIN), FractionNumber::from(0_u8)],
+            FormatPrecision::Max(3),
+            &options,
+        );
+        assert_eq!("-1.701e38", res[0]);
+        assert_eq!(" 0    e0 ", res[1]);
+
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "B" });
+        let res = fmt_align_fractions_with(
+            &[
+                FractionNumber::from(1_536_000_u32),
+                FractionNumber::from(512_u16),
+            ],
+            FormatPrecision::Max(3),
+            &options,
+        );
+        assert_eq!("  1.536 MB", res[0]);
+        assert_eq!("512      B", res[1]);
+    }
+
+    #[test]
+    fn test_fmt_grouping_separator() {
+        let options = AlignOptions::new().grouping_separator(Some(','));
+        let res = fmt_align_fractions_with(
+            &[
+                FractionNumber::F64(1_234_567.89),
+                FractionNumber::F64(-1000.0),
+                FractionNumber::F64(999.5),
+                FractionNumber::F64(f64::NAN),
+            ],
+            FormatPrecision::Max(2),
+            &options,
+        );
+        assert_eq!("1,234,567.89", res[0]);
+        assert_eq!("   -1,000   ", res[1]);
+        assert_eq!("      999.5 ", res[2]);
+        assert_eq!("      NaN   ", res[3]);
+
+        let options = AlignOptions::new().grouping_separator(Some('_'));
+        let res = fmt_align_fraction_strings_with(&["12_34_5", "-1234567"], &options);
+        assert_eq!("    12_345", res[0]);
+        assert_eq!("-1_234_567", res[1]);
+
+        // a grouping separator that is the decimal separator is ignored
+        let options = AlignOptions::new().grouping_separator(Some('.'));
+        assert_eq!(
+            vec!["1234.5"],
+            fmt_align_numbers_with(&[1234.5], FormatPrecision::Max(2), &options)
+        );
+        let options = AlignOptions::new()
+            .grouping_separator(Some(','))
+            .decimal_separator(',');
+        assert_eq!(
+            Ok(vec!["1234,5".to_string()]),
+            try_fmt_align_fraction_strings_with(&["1234,5"], &options)
+        );
+    }
+
+    #[test]
+    fn test_fmt_locales() {
+        let options = AlignOptions::new().locale(Locale::DE);
+        let res = fmt_align_fraction_strings_with(&["-1000,2", "3,1415", "1.000,5"], &options);
+        assert_eq!("-1.000,2   ", res[0]);
+        assert_eq!("     3,1415", res[1]);
+        assert_eq!(" 1.000,5   ", res[2]);
+
+        let options = AlignOptions::new()
+            .input_locale(Locale::DE)
+            .output_locale(Locale::CH);
+        let res = try_fmt_align_fraction_strings_with(&["1.000.000,5", "-42"], &options).unwrap();
+        assert_eq!("1'000'000.5", res[0]);
+        assert_eq!("      -42  ", res[1]);
+
+        let err = try_fmt_align_fraction_strings_with(&["1,5.5"], &options).unwrap_err();
+        assert_eq!(
+            AlignError::InvalidNumber {
+                index: 0,
+                input: "1,5.5".to_string(),
+                reason: InvalidNumberReason::InvalidCharacter('.'),
+            },
+            err
+        );
+
+        let options = AlignOptions::new().output_locale(Locale::FR);
+        let res = fmt_align_numbers_with(&[1234.5, -0.25], FormatPrecision::Max(2), &options);
+        assert_eq!("1\u{202F}234,5 ", res[0]);
+        assert_eq!("   -0,25", res[1]);
+    }
+
+    #[test]
+    fn test_fmt_groupings() {
+        let res = fmt_align_numbers_with(
+            &[123_456_789.0, -1000.0, 12.5],
+            FormatPrecision::Exact(2),
+            &AlignOptions::new().locale(Locale::IN),
+        );
+        assert_eq!("12,34,56,789.00", res[0]);
+        assert_eq!("      -1,000.00", res[1]);
+        assert_eq!("          12.50", res[2]);
+
+        let options = AlignOptions::new()
+            .grouping_separator(Some(','))
+            .grouping(Grouping::Myriad);
+        let res = fmt_align_fraction_strings_with(&["123456789", "-12345"], &options);
+        assert_eq!("1,2345,6789", res[0]);
+        assert_eq!("    -1,2345", res[1]);
+
+        let options = AlignOptions::new()
+            .grouping_separator(Some(' '))
+            .grouping(Grouping::Custom(&[1, 2]));
+        let res = fmt_align_fraction_strings_with(&["12345", "1"], &options);
+        assert_eq!("12 34 5", res[0]);
+        assert_eq!("      1", res[1]);
+
+        // the grouping also applies to the input
+        let options = AlignOptions::new()
+            .grouping_separator(Some(','))
+            .grouping(Grouping::Indian);
+        assert_eq!(
+            Ok(vec!["12,34,567.5".to_string(), "   -1,000  ".to_string()]),
+            try_fmt_align_fraction_strings_with(&["12,34,567.5", "-1,000"], &options)
+        );
+        let options = options.grouping(Grouping::Myriad);
+        assert_eq!(
+            Ok(vec!["1,2345,6789".to_string(), "    -1,2345".to_string()]),
+            try_fmt_align_fraction_strings_with(&["1,2345,6789", "-12345"], &options)
+        );
+        assert!(try_fmt_align_fraction_strings_with(&["123,456"], &options).is_err());
+    }
+
+    #[test]
+    fn test_fmt_display_width() {
+        // U+2212 minus sign and thin space grouping separator
+        let options = AlignOptions::new().locale(Locale::new('.', Some('\u{2009}')));
+        let res = fmt_align_fraction_strings_with(&["\u{2212}1\u{2009}000.5", "2.25"], &options);
+        assert_eq!("\u{2212}1\u{2009}000.5 ", res[0]);
+        assert_eq!("     2.25", res[1]);
+
+        // fullwidth digits are wider than ASCII digits
+        let res = fmt_align_fraction_strings(&["１２.５", "1.25"]);
+        assert_eq!("１２.５", res[0]);
+        assert_eq!("   1.25", res[1]);
+
+        // combining marks have no width
+        let res = fmt_align_fraction_strings(&["a\u{301}", "-12"]);
+        assert_eq!("  a\u{301}", res[0]);
+        assert_eq!("-12", res[1]);
+
+        let res = fmt_align_numbers_with(
+            &[4.7e-6, 100e-3],
+            FormatPrecision::Max(1),
+            &AlignOptions::new().notation(Notation::SiPrefix { unit: "F" }),
+        );
+        assert_eq!("  4.7 µF", res[0]);
+        assert_eq!("100   mF", res[1]);
+    }
+
+    #[test]
+    fn test_fmt_typographic() {
+        let options = AlignOptions::new().output_style(OutputStyle::Typographic);
+        let input = vec![
+            FractionNumber::F32(-42.0),
+            FractionNumber::F64(0.3214),
+            FractionNumber::F64(1000.0),
+            FractionNumber::F64(-1000.2),
+            FractionNumber::F64(2.0),
+        ];
+        let res = fmt_align_fractions_with(&input, FormatPrecision::Max(4), &options);
+        assert_eq!(
+            "\u{2007}\u{2007}\u{2212}42\u{2008}\u{2007}\u{2007}\u{2007}\u{2007}",
+            res[0]
+        );
+        assert_eq!("\u{2007}\u{2007}\u{2007}\u{2007}0.3214", res[1]);
+        assert_eq!(
+            "\u{2007}1000\u{2008}\u{2007}\u{2007}\u{2007}\u{2007}",
+            res[2]
+        );
+        assert_eq!("\u{2212}1000.2\u{2007}\u{2007}\u{2007}", res[3]);
+        assert_eq!(
+            "\u{2007}\u{2007}\u{2007}\u{2007}2\u{2008}\u{2007}\u{2007}\u{2007}\u{2007}",
+            res[4]
+        );
+
+        // missing grouping separators are replaced by punctuation spaces
+        let res = fmt_align_fraction_strings_with(
+            &["1234567", "-5", "12.5"],
+            &options.grouping_separator(Some(',')),
+        );
+        assert_eq!("1,234,567\u{2008}\u{2007}", res[0]);
+        assert_eq!(
+            "\u{2007}\u{2008}\u{2007}\u{2007}\u{2007}\u{2008}\u{2007}\u{2212}5\u{2008}\u{2007}",
+            res[1]
+        );
+        assert_eq!(
+            "\u{2007}\u{2008}\u{2007}\u{2007}\u{2007}\u{2008}\u{2007}12.5",
+            res[2]
+        );
+
+        let res = fmt_align_fraction_strings_with(&["1.5e-7", "-2e10"], &options);
+        assert_eq!("\u{2007}1.5e\u{2212}7", res[0]);
+        assert_eq!("\u{2212}2\u{2008}\u{2007}e10", res[1]);
+    }
+
+    #[test]
+    fn test_alignment_layout() {
+        let input = ["-42", "0.3214", "1000", "-1000.2", "2.00000"];
+        let options = AlignOptions::new().min_width(12);
+        let layout = AlignmentLayout::new(&input, &options);
+        assert_eq!(5, layout.whole_width());
+        assert_eq!(5, layout.fraction_width());
+        assert_eq!(0, layout.suffix_width());
+        assert_eq!(12, layout.width());
+
+        let expected = fmt_align_fraction_strings_with(&input, &options);
+        let rendered = layout
+            .display_all(&input)
+            .map(|entry| entry.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(expected, rendered);
+
+        // owned strings work as well
+        let input = vec!["1.5e-7".to_string(), "-12.25e3".to_string()];
+        let layout = AlignmentLayout::new(&input, &AlignOptions::new());
+        assert_eq!("  1.5 e-7", layout.display(&input[0]).to_string());
+        assert_eq!("-12.25e3 ", layout.display(&input[1]).to_string());
+    }
+
+    #[test]
+    fn test_alignment_layout_batches() {
+        // a layout from a sample aligns later batches identically
+        let layout = AlignmentLayout::new(&["-10.25", "100"], &AlignOptions::new());
+        assert_eq!([" -1.5 ", "  2   "], layout.align(&["-1.5", "2"])[..]);
+        assert_eq!(["100.25"], layout.align(&["100.25"])[..]);
+
+        let options = AlignOptions::new().grouping_separator(Some(','));
+        let layout = AlignmentLayout::from_bounds(4, 1, false, &options);
+        assert_eq!((5, 2), (layout.whole_width(), layout.fraction_width()));
+        assert_eq!(
+            ["    1.5", "1,234  "],
+            layout.align_numbers(&[1.5, 1234.0], FormatPrecision::Max(1))[..]
+        );
+
+        // widen
+        assert_eq!(
+            ["     1.5 ", "12,345.25"],
+            layout.align_numbers(&[1.5, 12345.25], FormatPrecision::Max(2))[..]
+        );
+        assert_eq!("12,345.25", layout.display("12345.25").to_string());
+
+        // truncate, fractional digits are cut off without rounding
+        let layout =
+            AlignmentLayout::from_bounds(4, 1, false, &options.overflow(Overflow::Truncate));
+        assert_eq!(
+            ["    1.5", "1,234.9", "#######"],
+            layout.align(&["1.5", "1234.99", "12345"])[..]
+        );
+        let layout =
+            AlignmentLayout::from_bounds(1, 0, true, &options.overflow(Overflow::Truncate));
+        assert_eq!(
+            ["-1", " 9", "##"],
+            layout.align(&["-1.75", "9.9", "-10"])[..]
+        );
+        let layout =
+            AlignmentLayout::from_bounds(1, 1, false, &options.overflow(Overflow::Truncate));
+        assert_eq!(
+            ["1  ", "1.1"],
+            layout.align_numbers(&[1.05, 1.15], FormatPrecision::Max(2))[..]
+        );
+        assert_eq!(
+            ["1.0", "1.1"],
+            layout.align_numbers(&[1.05, 1.15], FormatPrecision::Exact(2))[..]
+        );
+
+        // mark
+        let layout = AlignmentLayout::from_bounds(2, 2, false, &options.overflow(Overflow::Mark));
+        assert_eq!(
+            [" 1.25", "#####", "#####"],
+            layout.align(&["1.25", "1.125", "NaN"])[..]
+        );
+        let layout = AlignmentLayout::from_bounds(
+            2,
+            2,
+            false,
+            &options.overflow(Overflow::Mark).min_width(7),
+        );
+        assert_eq!("#######", layout.display("100").to_string());
+
+        // the suffix is derived from the notation
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "Ω" });
+        let layout = AlignmentLayout::from_bounds(3, 1, false, &options.overflow(Overflow::Mark));
+        assert_eq!(
+            ["  4.7  Ω", " 47   kΩ", "########"],
+            layout.align_numbers(&[4.7, 47000.0, 1.5e15], FormatPrecision::Max(1))[..]
+        );
+        let options = AlignOptions::new().notation(Notation::Scientific);
+        let layout =
+            AlignmentLayout::from_bounds(1, 2, true, &options.overflow(Overflow::Truncate));
+        assert_eq!(
+            [" 4.7 e0  ", "-1.25e-12", "#########"],
+            layout.align_numbers(&[4.7, -1.25e-12, 1e-100], FormatPrecision::Max(3))[..]
+        );
+    }
+
+    #[test]
+    fn test_alignment_layout_display_number() {
+        let numbers = [47000.0, 4.7, 1_200_000.0, 0.000_22];
+        let precision = FormatPrecision::Max(3);
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "Ω" });
+        let layout = AlignmentLayout::from_numbers(&numbers, precision, &options);
+        let rendered = numbers
+            .iter()
+            .map(|number| layout.display_number(number, precision).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fmt_align_numbers_with(&numbers, precision, &options),
+            rendered
+        );
+
+        // unnecessary zeroes are kept with an exact precision
+        let layout = AlignmentLayout::from_bounds(1, 2, true, &AlignOptions::new());
+        assert_eq!(
+            "-1.50",
+            layout
+                .display_number(&-1.5, FormatPrecision::Exact(2))
+                .to_string()
+        );
+        assert_eq!(
+            " 2   ",
+            layout
+                .display_number(&2, FormatPrecision::Max(2))
+                .to_string()
+        );
+
+        // numbers that don't fit into the buffer on the stack
+        let entry = layout.display_number(&f64::MAX, FormatPrecision::Exact(1));
+        assert_eq!(312, entry.to_string().len());
+    }
+
+    #[test]
+    fn test_write_aligned() {
+        let numbers = [-42.0, 0.3214, 1000.0, -1000.2, 2.0];
+        let options = AlignOptions::new().notation(Notation::Fixed);
+        let expected = fmt_align_numbers_with(&numbers, FormatPrecision::Max(4), &options);
+
+        let mut out = Vec::new();
+        write_aligned(
+            &mut out,
+            &numbers,
+            FormatPrecision::Max(4),
+            &options,
+            &LineFormat::new(),
+        )
+        .unwrap();
+        assert_eq!(format!("{}\n", expected.join("\n")).as_bytes(), &out[..]);
+
+        let mut out = String::new();
+        write_aligned_fmt(
+            &mut out,
+            &numbers,
+            FormatPrecision::Exact(1),
+            &options,
+            &LineFormat::new().prefix("> ").terminator(";"),
+        )
+        .unwrap();
+        assert_eq!(">   -42.0;>     0.3;>  1000.0;> -1000.2;>     2.0;", out);
+
+        // SI prefixes are aligned like in fmt_align_numbers_with
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "Ω" });
+        let mut out = String::new();
+        write_aligned_fmt(
+            &mut out,
+            &[4.7, 47000.0],
+            FormatPrecision::Max(1),
+            &options,
+            &LineFormat::new(),
+        )
+        .unwrap();
+        assert_eq!(" 4.7  Ω\n47   kΩ\n", out);
+
+        let mut out = Vec::new();
+        write_aligned_strings(
+            &mut out,
+            &["1.5e-7", "-3"],
+            &AlignOptions::new(),
+            &LineFormat::new().suffix("|"),
+        )
+        .unwrap();
+        assert_eq!(&b" 1.5e-7|\n-3     |\n"[..], &out[..]);
+
+        let mut out = String::new();
+        write_aligned_strings_fmt(&mut out, &[] as &[&str], &options, &LineFormat::new()).unwrap();
+        assert!(out.is_empty());
+    }
+
+    /// A number whose formatting fails for `None`.
+    struct FailingNumber(Option<f64>);
+
+    impl AlignableNumber for FailingNumber {
+        fn is_negative(&self) -> bool {
+            self.0.map_or(false, |number| number < 0.0)
+        }
+
+        fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+            self.0.ok_or(fmt::Error)?.write_fixed(precision, out)
+        }
+    }
+
+    #[test]
+    fn test_failing_number() {
+        let numbers = [FailingNumber(Some(-1.5)), FailingNumber(None)];
+        let options = AlignOptions::new();
+
+        // the infallible functions render the number as an empty entry
+        let aligned = fmt_align_numbers_with(&numbers, FormatPrecision::Max(2), &options);
+        assert_eq!(vec!["-1.5", "    "], aligned);
+        let layout = AlignmentLayout::from_numbers(&numbers, FormatPrecision::Max(2), &options);
+        assert_eq!(
+            aligned,
+            layout
+                .display_all(&["-1.5", ""])
+                .map(|entry| entry.to_string())
+                .collect::<Vec<_>>()
+        );
+        let mut aligner = StreamingAligner::new().precision(FormatPrecision::Max(2));
+        for number in numbers.iter() {
+            aligner.push(number);
+        }
+        let lines = aligner.lines().map(|line| line.to_string());
+        assert_eq!(aligned, lines.collect::<Vec<_>>());
+
+        // the fallible ones fail
+        let line_format = LineFormat::new();
+        let mut out = Vec::new();
+        let result = write_aligned(
+            &mut out,
+            &numbers,
+            FormatPrecision::Max(2),
+            &options,
+            &line_format,
+        );
+        assert!(result.is_err());
+        assert!(out.is_empty());
+        let mut out = String::new();
+        let result = write_aligned_fmt(
+            &mut out,
+            &numbers,
+            FormatPrecision::Max(2),
+            &options,
+            &line_format,
+        );
+        assert_eq!(Err(fmt::Error), result);
+        let entry = layout.display_number(&numbers[1], FormatPrecision::Max(2));
+        assert!(fmt::write(&mut String::new(), format_args!("{}", entry)).is_err());
+    }
+
     // tests that we get "NaN" and not a panic or so
     #[test]
     fn test_fmt_nan() {
@@ -0,0 +1,178 @@
+//! Locale dependent number formats, see [`Locale`] and [`Grouping`].
+
+/// How the digits of the whole part are grouped by grouping separators.
+///
+/// The groups are counted from the decimal separator to the left. For all groupings,
+/// the grouping separators of all entries of a list are aligned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Grouping {
+    /// Groups of the same size, e.g. `1,234,567` for `Fixed(3)`.
+    Fixed(u8),
+    /// Indian grouping: a group of three followed by groups of two, e.g. `12,34,56,789`
+    /// (lakh and crore).
+    Indian,
+    /// Chinese and Japanese myriad grouping: groups of four, e.g. `1234,5678`.
+    Myriad,
+    /// Custom group sizes from the decimal separator to the left. The last size is
+    /// repeated, e.g. `Custom(&[3, 2])` is the same as [`Self::Indian`]. A size of zero
+    /// stops the grouping for all remaining digits.
+    Custom(&'static [u8]),
+}
+
+impl Grouping {
+    /// Whether a grouping separator is placed in front of the digit of the whole part
+    /// that has `digits_to_the_right` digits to the right of it. For the default
+    /// grouping, this is true for 3, 6, 9, etc.
+    pub(crate) fn is_separator_position(&self, digits_to_the_right: usize) -> bool {
+        let fixed;
+        let sizes: &[u8] = match self {
+            Self::Fixed(size) => {
+                fixed = [*size];
+                &fixed
+            }
+            Self::Indian => &[3, 2],
+            Self::Myriad => &[4],
+            Self::Custom(sizes) => sizes,
+        };
+
+        let mut position = 0;
+        for index in 0.. {
+            let size = match sizes.get(index).or_else(|| sizes.last()) {
+                Some(size) if *size > 0 => usize::from(*size),
+                _ => return false,
+            };
+            position += size;
+            if position >= digits_to_the_right {
+                break;
+            }
+        }
+        position == digits_to_the_right
+    }
+
+    /// Returns the number of grouping separators in a whole part with `digits` digits.
+    pub(crate) fn separator_count(&self, digits: usize) -> usize {
+        (1..digits)
+            .filter(|digits_to_the_right| self.is_separator_position(*digits_to_the_right))
+            .count()
+    }
+}
+
+impl Default for Grouping {
+    fn default() -> Self {
+        Self::Fixed(3)
+    }
+}
+
+/// The decimal separator, grouping separator and [`Grouping`] of a number format, such
+/// as `1,000.5` in English or `1.000,5` in German.
+///
+/// A locale is used for parsing string input and for rendering output, see
+/// [`crate::AlignOptions::locale`]. Using different locales for input and output
+/// converts between the conventions.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// use fraction_list_fmt_align::{fmt_align_fraction_strings_with, AlignOptions, Locale};
+///
+/// let options = AlignOptions::new()
+///     .input_locale(Locale::DE)
+///     .output_locale(Locale::EN);
+/// let aligned = fmt_align_fraction_strings_with(&["1.000,5", "3,1415"], &options);
+/// assert_eq!(aligned, ["1,000.5   ", "    3.1415"]);
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Locale {
+    pub(crate) decimal_separator: char,
+    pub(crate) grouping_separator: Option<char>,
+    pub(crate) grouping: Grouping,
+}
+
+impl Locale {
+    /// `.` as decimal separator and no grouping, e.g. `1000.5`. This is the format of
+    /// Rust's formatting machinery and the default.
+    pub const POSIX: Self = Self::new('.', None);
+    /// English: `.` as decimal separator and `,` for grouping, e.g. `1,000.5`.
+    pub const EN: Self = Self::new('.', Some(','));
+    /// German: `,` as decimal separator and `.` for grouping, e.g. `1.000,5`.
+    pub const DE: Self = Self::new(',', Some('.'));
+    /// French: `,` as decimal separator and a narrow no-break space (`U+202F`) for
+    /// grouping, e.g. `1 000,5`.
+    pub const FR: Self = Self::new(',', Some('\u{202F}'));
+    /// Swiss: `.` as decimal separator and `'` for grouping, e.g. `1'000.5`.
+    pub const CH: Self = Self::new('.', Some('\''));
+    /// Indian English: `.` as decimal separator and `,` for [`Grouping::Indian`], e.g.
+    /// `12,34,567.5`.
+    pub const IN: Self = Self::new('.', Some(',')).with_grouping(Grouping::Indian);
+
+    /// Creates a locale with the given decimal separator and optional grouping
+    /// separator. The digits are grouped by three.
+    pub const fn new(decimal_separator: char, grouping_separator: Option<char>) -> Self {
+        Self {
+            decimal_separator,
+            grouping_separator,
+            grouping: Grouping::Fixed(3),
+        }
+    }
+
+    /// Returns the locale with the given [`Grouping`].
+    pub const fn with_grouping(mut self, grouping: Grouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Returns the decimal separator.
+    pub const fn decimal_separator(&self) -> char {
+        self.decimal_separator
+    }
+
+    /// Returns the grouping separator, if any. A grouping separator that is the same
+    /// as the decimal separator is ignored, as the numbers could not be read back.
+    pub const fn grouping_separator(&self) -> Option<char> {
+        match self.grouping_separator {
+            Some(separator) if separator != self.decimal_separator => Some(separator),
+            _ => None,
+        }
+    }
+
+    /// Returns the [`Grouping`].
+    pub const fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::POSIX
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn separator_positions(grouping: Grouping) -> Vec<usize> {
+        (1..12)
+            .filter(|digits| grouping.is_separator_position(*digits))
+            .collect()
+    }
+
+    #[test]
+    fn test_grouping() {
+        assert_eq!(vec![3, 6, 9], separator_positions(Grouping::Fixed(3)));
+        assert_eq!(vec![3, 5, 7, 9, 11], separator_positions(Grouping::Indian));
+        assert_eq!(vec![4, 8], separator_positions(Grouping::Myriad));
+        assert_eq!(
+            vec![2, 5, 6, 7],
+            separator_positions(Grouping::Custom(&[2, 3, 1, 1, 0]))
+        );
+        assert!(separator_positions(Grouping::Fixed(0)).is_empty());
+        assert!(separator_positions(Grouping::Custom(&[])).is_empty());
+
+        assert_eq!(0, Grouping::Fixed(3).separator_count(3));
+        assert_eq!(1, Grouping::Fixed(3).separator_count(4));
+        assert_eq!(3, Grouping::Indian.separator_count(9));
+    }
+}
@@ -0,0 +1,342 @@
+//! Helpers to format numbers in engineering notation and with SI prefixes.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+/// The SI prefixes supported by [`crate::Notation::SiPrefix`] with their exponents.
+const SI_PREFIXES: [(i32, &str); 9] = [
+    (-12, "p"),
+    (-9, "n"),
+    (-6, "µ"),
+    (-3, "m"),
+    (0, ""),
+    (3, "k"),
+    (6, "M"),
+    (9, "G"),
+    (12, "T"),
+];
+
+/// Smallest exponent that has an SI prefix.
+pub(crate) const SI_MIN_EXPONENT: i32 = -12;
+/// Largest exponent that has an SI prefix.
+pub(crate) const SI_MAX_EXPONENT: i32 = 12;
+
+/// Returns the SI prefix for an exponent that is a multiple of three in the range
+/// of [`SI_MIN_EXPONENT`] and [`SI_MAX_EXPONENT`].
+/// * `3` => `k`
+/// * `-6` => `µ`
+/// * `0` => ``
+pub(crate) fn si_prefix(exponent: i32) -> &'static str {
+    SI_PREFIXES
+        .iter()
+        .find(|(e, _)| *e == exponent)
+        .map(|(_, prefix)| *prefix)
+        .expect("exponent must have an SI prefix")
+}
+
+/// Formats a non-zero, finite value in engineering notation, i.e. with an exponent
+/// that is a multiple of three. The exponent is clamped to `min_exponent..=max_exponent`,
+/// so that the mantissa may be smaller than `1` or larger than `1000` for values out
+/// of this range.
+/// * `exponent`: The exponent of the value in scientific notation. Might be one too
+///   large if it comes from a rounded representation.
+/// * `scientific`: Formats the value in scientific notation with the given number of
+///   fractional digits, e.g. `-4.70e4`.
+/// * `fixed`: Formats the value in plain decimal notation with the given number of
+///   fractional digits, e.g. `-47000.00`.
+///
+/// Returns the mantissa with exactly `precision` fractional digits and the exponent.
+/// * `47000.0` => `("47.000", 3)` (precision 3)
+/// * `0.0000000033` => `("3.30", -9)` (precision 2)
+pub(crate) fn format_engineering(
+    mut exponent: i32,
+    scientific: &dyn Fn(usize) -> String,
+    fixed: &dyn Fn(usize) -> String,
+    precision: usize,
+    min_exponent: i32,
+    max_exponent: i32,
+) -> (String, i32) {
+    let engineering_exponent_of =
+        |exponent: i32| (exponent.div_euclid(3) * 3).clamp(min_exponent, max_exponent);
+    loop {
+        let engineering_exponent = engineering_exponent_of(exponent);
+        let shift = exponent - engineering_exponent;
+        if shift < 0 {
+            // Tiny value below the smallest exponent: All digits down to
+            // 10^(engineering_exponent - precision) are required.
+            let fixed = fixed(precision + engineering_exponent.unsigned_abs() as usize);
+            return (
+                shift_decimal_point(&fixed, -engineering_exponent),
+                engineering_exponent,
+            );
+        }
+
+        let scientific = scientific(precision + shift as usize);
+        let mantissa = scientific.split('e').next().unwrap();
+        let rounded_exponent = scientific_exponent(&scientific);
+        if rounded_exponent < exponent {
+            // the exponent was too large, retry with the exact one
+            exponent = rounded_exponent;
+            continue;
+        }
+        if rounded_exponent > exponent {
+            // Rounding resulted in the next power of ten, e.g. 999.96 => 1.000e3.
+            // The mantissa is a one followed by zeroes with the new exponent.
+            let sign = if mantissa.starts_with('-') { "-" } else { "" };
+            let engineering_exponent = engineering_exponent_of(rounded_exponent);
+            let shift = rounded_exponent - engineering_exponent;
+            let mantissa = format!(
+                "{}1.{}",
+                sign,
+                "0".repeat(precision + shift.max(0) as usize)
+            );
+            return (shift_decimal_point(&mantissa, shift), engineering_exponent);
+        }
+        return (shift_decimal_point(mantissa, shift), engineering_exponent);
+    }
+}
+
+/// Returns the exponent of a number that is formatted in scientific notation.
+/// * `1.5e-7` => `-7`
+pub(crate) fn scientific_exponent(scientific: &str) -> i32 {
+    scientific
+        .split('e')
+        .nth(1)
+        .and_then(|exponent| exponent.parse().ok())
+        .expect("number must be formatted in scientific notation")
+}
+
+/// How a value that lies exactly halfway between two rounded values is rounded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Ties {
+    /// To the value with an even last digit, like floating point values are formatted.
+    ToEven,
+    /// To the value with the larger magnitude, like decimals are usually rounded.
+    AwayFromZero,
+}
+
+/// Formats a number in plain decimal notation, such as `-12345` or `0.0120`, in
+/// scientific notation with `precision` fractional digits of the mantissa.
+/// * `-12345`, `2` => `-1.23e4`
+/// * `12500`, `1` => `1.2e4` (ties to even) or `1.3e4` (ties away from zero)
+/// * `99`, `0` => `1e2`
+/// * `0.0120`, `1` => `1.2e-2`
+pub(crate) fn format_decimal_scientific(decimal: &str, precision: usize, ties: Ties) -> String {
+    let (sign, decimal) = decimal
+        .strip_prefix('-')
+        .map_or(("", decimal), |decimal| ("-", decimal));
+    let (whole, fraction) = decimal.split_once('.').unwrap_or((decimal, ""));
+    let digits = format!("{}{}", whole, fraction);
+
+    // the mantissa starts with the first digit that is not zero
+    let (mut exponent, mut mantissa) = digits.bytes().position(|d| d != b'0').map_or_else(
+        || (0, vec![b'0']),
+        |first| {
+            (
+                whole.len() as i32 - 1 - first as i32,
+                digits.as_bytes()[first..].to_vec(),
+            )
+        },
+    );
+    if round_digits(&mut mantissa, precision + 1, ties) {
+        mantissa.pop();
+        exponent += 1;
+    }
+
+    let mantissa = String::from_utf8(mantissa).unwrap();
+    let (first, rest) = mantissa.split_at(1);
+    if rest.is_empty() {
+        format!("{}{}e{}", sign, first, exponent)
+    } else {
+        format!("{}{}.{}e{}", sign, first, rest, exponent)
+    }
+}
+
+/// Rounds a non-negative number in plain decimal notation, such as `1234.5`, to
+/// `precision` fractional digits. Unnecessary leading zeroes are removed.
+/// * `2.675`, `2` => `2.68` (ties away from zero)
+/// * `99.96`, `1` => `100.0`
+/// * `0.5`, `3` => `0.500`
+pub(crate) fn format_decimal_fixed(decimal: &str, precision: usize, ties: Ties) -> String {
+    let (whole, fraction) = decimal.split_once('.').unwrap_or((decimal, ""));
+    let mut digits = format!("{}{}", whole, fraction).into_bytes();
+    let mut whole_len = whole.len();
+    if round_digits(&mut digits, whole_len + precision, ties) {
+        whole_len += 1;
+    }
+
+    let digits = String::from_utf8(digits).unwrap();
+    let (whole, fraction) = digits.split_at(whole_len);
+    let whole = whole.trim_start_matches('0');
+    let whole = if whole.is_empty() { "0" } else { whole };
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Rounds the decimal digits to the first `len` digits or pads them with zeroes.
+/// Returns whether rounding resulted in the next power of ten, i.e. a one followed by
+/// `len` zeroes.
+/// * `1250`, `2` => `13` (ties away from zero)
+/// * `995`, `2` => `100`
+fn round_digits(digits: &mut Vec<u8>, len: usize, ties: Ties) -> bool {
+    if digits.len() <= len {
+        digits.resize(len, b'0');
+        return false;
+    }
+    let rest = &digits[len..];
+    let is_half = rest[0] == b'5' && rest[1..].iter().all(|d| *d == b'0');
+    let round_up = match ties {
+        _ if !is_half => rest[0] >= b'5',
+        Ties::ToEven => len
+            .checked_sub(1)
+            .map_or(false, |last| (digits[last] - b'0') % 2 == 1),
+        Ties::AwayFromZero => true,
+    };
+    digits.truncate(len);
+    if round_up {
+        match digits.iter().rposition(|d| *d != b'9') {
+            Some(index) => {
+                digits[index] += 1;
+                digits[index + 1..].fill(b'0');
+            }
+            None => {
+                // all digits were nines
+                digits.fill(b'0');
+                digits.insert(0, b'1');
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Moves the decimal point of a formatted number `shift` places to the right (or to the
+/// left if `shift` is negative). Unnecessary leading zeroes are removed.
+/// * `-9.996`, `2` => `-999.6`
+/// * `0.0000000033`, `9` => `3.3`
+/// * `4.7`, `-1` => `0.47`
+fn shift_decimal_point(number: &str, shift: i32) -> String {
+    let (sign, number) = number
+        .strip_prefix('-')
+        .map_or(("", number), |number| ("-", number));
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let mut digits = format!("{}{}", whole, fraction);
+    let mut point = whole.len() as i32 + shift;
+    if point <= 0 {
+        digits.insert_str(0, &"0".repeat((1 - point) as usize));
+        point = 1;
+    }
+    let point = point as usize;
+    if point > digits.len() {
+        digits.push_str(&"0".repeat(point - digits.len()));
+    }
+
+    let (whole, fraction) = digits.split_at(point);
+    let whole = whole.trim_start_matches('0');
+    let whole = if whole.is_empty() { "0" } else { whole };
+    if fraction.is_empty() {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_shift_decimal_point() {
+        assert_eq!("-999.6", shift_decimal_point("-9.996", 2));
+        assert_eq!("3.3", shift_decimal_point("0.0000000033", 9));
+        assert_eq!("0.47", shift_decimal_point("4.7", -1));
+        assert_eq!("4700", shift_decimal_point("4.7", 3));
+        assert_eq!("1", shift_decimal_point("1", 0));
+    }
+
+    #[test]
+    fn test_format_engineering() {
+        let format = |val: f64, precision, min_exponent, max_exponent| {
+            format_engineering(
+                scientific_exponent(&format!("{:e}", val)),
+                &|precision| format!("{:.*e}", precision, val),
+                &|precision| format!("{:.*}", precision, val),
+                precision,
+                min_exponent,
+                max_exponent,
+            )
+        };
+        let f = |val, precision| format(val, precision, i32::MIN, i32::MAX);
+        assert_eq!(("47.000".to_string(), 3), f(47000.0, 3));
+        assert_eq!(("3.30".to_string(), -9), f(0.000_000_003_3, 2));
+        assert_eq!(("-1.2".to_string(), 6), f(-1_200_000.0, 1));
+        assert_eq!(("1.0".to_string(), 3), f(999.96, 1));
+        assert_eq!(("-1.0".to_string(), 3), f(-999.96, 1));
+        assert_eq!(("999.9".to_string(), 0), f(999.94, 1));
+        assert_eq!(("10.0".to_string(), 0), f(9.96, 1));
+        assert_eq!(("10".to_string(), 3), f(9996.0, 0));
+        assert_eq!(("100.0".to_string(), 21), f(1e23, 1));
+
+        assert_eq!(
+            ("0.0010".to_string(), -12),
+            format(1e-15, 4, SI_MIN_EXPONENT, SI_MAX_EXPONENT)
+        );
+        assert_eq!(
+            ("5000".to_string(), 12),
+            format(5e15, 0, SI_MIN_EXPONENT, SI_MAX_EXPONENT)
+        );
+        assert_eq!(
+            ("10000".to_string(), 12),
+            format(9.9999e15, 0, SI_MIN_EXPONENT, SI_MAX_EXPONENT)
+        );
+    }
+
+    #[test]
+    fn test_format_decimal_scientific() {
+        let f = |decimal, precision| format_decimal_scientific(decimal, precision, Ties::ToEven);
+        assert_eq!("-1.23e4", f("-12345", 2));
+        assert_eq!("1.24e4", f("12350", 2));
+        assert_eq!("1.2e4", f("12250", 1));
+        assert_eq!("1.3e4", f("12551", 1));
+        assert_eq!("1e2", f("99", 0));
+        assert_eq!("7.000e0", f("7", 3));
+        assert_eq!("0e0", f("0", 0));
+        assert_eq!("0.00e0", f("0.000", 2));
+        assert_eq!("1.2e-2", f("0.0120", 1));
+        assert_eq!("-1.0e0", f("-0.995", 1));
+        assert_eq!("1.8446744073709551615e19", f(&u64::MAX.to_string(), 19));
+
+        let f =
+            |decimal, precision| format_decimal_scientific(decimal, precision, Ties::AwayFromZero);
+        assert_eq!("1.3e4", f("12500", 1));
+        assert_eq!("1.2e4", f("12250", 1));
+        assert_eq!("-1.3e-3", f("-0.00125", 1));
+    }
+
+    #[test]
+    fn test_format_decimal_fixed() {
+        let f = |decimal, precision| format_decimal_fixed(decimal, precision, Ties::AwayFromZero);
+        assert_eq!("2.68", f("2.675", 2));
+        assert_eq!("1.01", f("1.005", 2));
+        assert_eq!("0.13", f("0.125", 2));
+        assert_eq!("12345678901234567.5", f("12345678901234567.5", 1));
+        assert_eq!("12345678901234568", f("12345678901234567.5", 0));
+        assert_eq!("100.0", f("99.96", 1));
+        assert_eq!("0.500", f("0.5", 3));
+        assert_eq!("1", f(".5", 0));
+        assert_eq!("0", f("000.4", 0));
+        assert_eq!("0.12", format_decimal_fixed("0.125", 2, Ties::ToEven));
+    }
+
+    #[test]
+    fn test_si_prefix() {
+        assert_eq!("k", si_prefix(3));
+        assert_eq!("µ", si_prefix(-6));
+        assert_eq!("", si_prefix(0));
+    }
+}
@@ -0,0 +1,592 @@
+//! The [`AlignableNumber`] trait and the formatting of numbers.
+
+#[cfg(feature = "alloc")]
+use crate::align::write_to_string;
+use crate::fixed::NumberBuffer;
+#[cfg(feature = "alloc")]
+use crate::notation::{self, Ties};
+use crate::parts::FractionParts;
+use crate::{AlignOptions, FormatPrecision, Notation};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use alloc::{format, vec};
+#[cfg(feature = "alloc")]
+use core::cell::Cell;
+use core::fmt;
+
+/// A number that can be formatted and aligned with [`crate::fmt_align_numbers`].
+///
+/// This is implemented for all primitive floating point and integer types, for
+/// [`crate::FractionNumber`], and, with the `alloc` feature, for decimal strings.
+/// Implement it for your own numeric types, such as fixed-point or decimal types, to
+/// align them.
+///
+/// If writing a number fails, the infallible functions, such as
+/// [`crate::fmt_align_numbers`], render it as an empty entry, while
+/// [`crate::write_aligned`] and [`crate::write_aligned_fmt`] return the error.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// use fraction_list_fmt_align::{fmt_align_numbers, AlignableNumber, FormatPrecision};
+/// use std::fmt;
+///
+/// /// Thousandths of a unit.
+/// struct Millis(i64);
+///
+/// impl AlignableNumber for Millis {
+///     fn is_negative(&self) -> bool {
+///         self.0 < 0
+///     }
+///
+///     fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+///         let abs = self.0.unsigned_abs();
+///         let fraction = format!("{:03}", abs % 1000);
+///         write!(out, "{}", abs / 1000)?;
+///         if precision > 0 {
+///             // this example truncates instead of rounding
+///             let digits = fraction.len().min(precision);
+///             write!(out, ".{}{}", &fraction[..digits], "0".repeat(precision - digits))?;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let aligned = fmt_align_numbers(&[Millis(-42_000), Millis(1_250)], FormatPrecision::Max(3));
+/// assert_eq!(aligned, ["-42   ", "  1.25"]);
+/// # }
+/// ```
+pub trait AlignableNumber {
+    /// Whether the number is negative. A sign is only printed for negative numbers.
+    fn is_negative(&self) -> bool;
+
+    /// Writes the absolute value in plain decimal notation, i.e. the digits of the whole
+    /// part followed by a `.` and exactly `precision` fractional digits, for example
+    /// `1234.50` for a precision of two. If `precision` is zero, the `.` is omitted.
+    ///
+    /// Values that are not finite write a textual representation, such as `NaN` or
+    /// `inf`, instead.
+    fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result;
+
+    /// Writes the absolute value in scientific notation with exactly `precision`
+    /// fractional digits of the mantissa, for example `1.23e-7` for a precision of two.
+    ///
+    /// The default implementation derives the digits from [`Self::write_fixed`] with
+    /// `precision` fractional digits. Types that can represent values with more
+    /// fractional digits, i.e. very small values, should override this.
+    ///
+    /// Only available with the `alloc` feature, as all notations except
+    /// [`Notation::Fixed`](crate::Notation::Fixed) require it.
+    #[cfg(feature = "alloc")]
+    fn write_scientific(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        let mut fixed = String::new();
+        self.write_fixed(precision, &mut fixed)?;
+        if !fixed.bytes().any(|b| b.is_ascii_digit()) {
+            // not finite
+            return out.write_str(&fixed);
+        }
+        out.write_str(&notation::format_decimal_scientific(
+            &fixed,
+            precision,
+            Ties::ToEven,
+        ))
+    }
+}
+
+impl<T: AlignableNumber + ?Sized> AlignableNumber for &T {
+    fn is_negative(&self) -> bool {
+        (**self).is_negative()
+    }
+
+    fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        (**self).write_fixed(precision, out)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write_scientific(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        (**self).write_scientific(precision, out)
+    }
+}
+
+/// Strings in plain decimal notation with `.` as the decimal separator and an optional
+/// sign, such as `-1234.5`. They are rounded exactly, i.e. without a detour over
+/// floating point values, and ties are rounded away from zero, e.g. `0.125` becomes
+/// `0.13` with two fractional digits. Formatting any other string fails.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{fmt_align_numbers, FormatPrecision};
+///
+/// let aligned = fmt_align_numbers(&["2.675", "-12345678901234567.25"], FormatPrecision::Max(1));
+/// assert_eq!(aligned, ["                 2.7", "-12345678901234567.3"]);
+/// ```
+#[cfg(feature = "alloc")]
+impl AlignableNumber for str {
+    fn is_negative(&self) -> bool {
+        self.starts_with('-')
+    }
+
+    fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        let decimal = unsigned_decimal(self).ok_or(fmt::Error)?;
+        out.write_str(&notation::format_decimal_fixed(
+            decimal,
+            precision,
+            Ties::AwayFromZero,
+        ))
+    }
+
+    fn write_scientific(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        let decimal = unsigned_decimal(self).ok_or(fmt::Error)?;
+        out.write_str(&notation::format_decimal_scientific(
+            decimal,
+            precision,
+            Ties::AwayFromZero,
+        ))
+    }
+}
+
+/// Returns the number without its sign if it is in plain decimal notation.
+/// * `-1234.5` => `1234.5`
+/// * `1.5e3` => `None`
+#[cfg(feature = "alloc")]
+fn unsigned_decimal(decimal: &str) -> Option<&str> {
+    let unsigned = decimal
+        .strip_prefix(|c| c == '-' || c == '+')
+        .unwrap_or(decimal);
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let is_digits = |digits: &str| digits.bytes().all(|d| d.is_ascii_digit());
+    if whole.len() + fraction.len() > 0 && is_digits(whole) && is_digits(fraction) {
+        Some(unsigned)
+    } else {
+        None
+    }
+}
+
+macro_rules! impl_alignable_float {
+    ($($ty:ty),+) => {
+        $(
+            impl AlignableNumber for $ty {
+                fn is_negative(&self) -> bool {
+                    // "-0" is printed like by the standard library, "-NaN" is not
+                    self.is_sign_negative() && !self.is_nan()
+                }
+
+                fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+                    let abs = if self.is_sign_negative() { -*self } else { *self };
+                    write!(out, "{val:.precision$}", val = abs, precision = precision)
+                }
+
+                #[cfg(feature = "alloc")]
+                fn write_scientific(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+                    let abs = if self.is_sign_negative() { -*self } else { *self };
+                    write!(out, "{val:.precision$e}", val = abs, precision = precision)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_alignable_signed_integer {
+    ($($ty:ty),+) => {
+        $(
+            impl AlignableNumber for $ty {
+                fn is_negative(&self) -> bool {
+                    *self < 0
+                }
+
+                fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+                    write_integer_fixed(self.unsigned_abs(), precision, out)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_alignable_unsigned_integer {
+    ($($ty:ty),+) => {
+        $(
+            impl AlignableNumber for $ty {
+                fn is_negative(&self) -> bool {
+                    false
+                }
+
+                fn write_fixed(&self, precision: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+                    write_integer_fixed(self, precision, out)
+                }
+            }
+        )+
+    };
+}
+
+impl_alignable_float!(f32, f64);
+impl_alignable_signed_integer!(i8, i16, i32, i64, i128, isize);
+impl_alignable_unsigned_integer!(u8, u16, u32, u64, u128, usize);
+
+/// Writes an integer with `precision` fractional digits, i.e. `42.00` for a precision
+/// of two.
+fn write_integer_fixed(
+    integer: impl fmt::Display,
+    precision: usize,
+    out: &mut dyn fmt::Write,
+) -> fmt::Result {
+    write!(out, "{}", integer)?;
+    if precision > 0 {
+        out.write_char('.')?;
+        for _ in 0..precision {
+            out.write_char('0')?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the number with the given precision and notation, including the sign, and
+/// returns the SI prefix for [`Notation::SiPrefix`]. Plain decimal notation is written
+/// directly into `out`; without the `alloc` feature, all other notations fail.
+pub(crate) fn write_number<T: AlignableNumber + ?Sized>(
+    number: &T,
+    precision: FormatPrecision,
+    notation: Notation,
+    out: &mut dyn fmt::Write,
+) -> Result<Option<&'static str>, fmt::Error> {
+    match notation {
+        Notation::Fixed => {
+            if number.is_negative() {
+                out.write_char('-')?;
+            }
+            number.write_fixed(precision.val(), out)?;
+            Ok(None)
+        }
+        #[cfg(feature = "alloc")]
+        _ => {
+            let (string, prefix) = format_number(number, precision, notation)?;
+            out.write_str(&string)?;
+            Ok(prefix)
+        }
+        #[cfg(not(feature = "alloc"))]
+        _ => Err(fmt::Error),
+    }
+}
+
+/// Writes the suffix of a number in [`Notation::SiPrefix`], i.e. a space, the SI
+/// prefix and the unit. With `reserve_prefix`, a missing prefix is replaced by padding,
+/// so that the unit is aligned with the units of the numbers with a prefix.
+fn write_si_suffix(
+    prefix: &str,
+    unit: &str,
+    reserve_prefix: bool,
+    options: &AlignOptions,
+    out: &mut dyn fmt::Write,
+) -> fmt::Result {
+    match prefix {
+        "" if reserve_prefix => write!(out, " {}{}", options.effective_padding_char(), unit),
+        "" if unit.is_empty() => Ok(()),
+        prefix => write!(out, " {}{}", prefix, unit),
+    }
+}
+
+/// Returns the parts of a formatted number with the suffix of [`write_si_suffix`], if
+/// any.
+fn parts_with_suffix<'a>(string: &'a str, suffix: &'a str) -> FractionParts<'a> {
+    let parts = FractionParts::parse(string, '.');
+    if suffix.is_empty() {
+        parts
+    } else {
+        FractionParts {
+            suffix: Some(suffix),
+            ..parts
+        }
+    }
+}
+
+/// A number that is formatted according to the [`AlignOptions`] into buffers on the
+/// stack, see [`NumberBuffer`]. The default is an empty entry.
+#[derive(Debug, Default)]
+pub(crate) struct BufferedNumber {
+    string: NumberBuffer,
+    /// SI prefix and unit, empty for other notations.
+    suffix: NumberBuffer,
+    #[cfg(feature = "alloc")]
+    has_prefix: bool,
+}
+
+impl BufferedNumber {
+    /// Formats the number. With `reserve_prefix`, a missing SI prefix is replaced by
+    /// padding, see [`FormattedNumbers`].
+    pub(crate) fn new<T: AlignableNumber + ?Sized>(
+        number: &T,
+        precision: FormatPrecision,
+        options: &AlignOptions,
+        reserve_prefix: bool,
+    ) -> Result<Self, fmt::Error> {
+        let mut string = NumberBuffer::new();
+        let prefix = write_number(number, precision, options.notation, &mut string)?;
+        let mut suffix = NumberBuffer::new();
+        if let (Notation::SiPrefix { unit }, Some(prefix)) = (options.notation, prefix) {
+            write_si_suffix(prefix, unit, reserve_prefix, options, &mut suffix)?;
+        }
+        Ok(Self {
+            string,
+            suffix,
+            #[cfg(feature = "alloc")]
+            has_prefix: !prefix.unwrap_or("").is_empty(),
+        })
+    }
+
+    /// Whether the number has an SI prefix.
+    #[cfg(feature = "alloc")]
+    pub(crate) const fn has_prefix(&self) -> bool {
+        self.has_prefix
+    }
+
+    /// Returns the parts of the formatted number.
+    pub(crate) fn parts(&self) -> FractionParts<'_> {
+        parts_with_suffix(self.string.as_str(), self.suffix.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Formats the number with the given precision and notation, including the sign. For
+/// [`Notation::SiPrefix`], the mantissa and the SI prefix are returned separately.
+/// Fails if the [`AlignableNumber`] implementation fails.
+pub(crate) fn format_number<T: AlignableNumber + ?Sized>(
+    number: &T,
+    precision: FormatPrecision,
+    notation: Notation,
+) -> Result<(String, Option<&'static str>), fmt::Error> {
+    let precision = precision.val();
+    let formatted = Formatter::new(number);
+    let result = match notation {
+        Notation::Fixed => (formatted.fixed(precision), None),
+        Notation::Scientific => (formatted.scientific(precision), None),
+        Notation::Auto => {
+            let scientific = formatted.scientific(precision);
+            let is_zero = !scientific.bytes().any(|b| (b'1'..=b'9').contains(&b));
+            match scientific_exponent(&scientific) {
+                // very large or tiny
+                Some(exponent) if !is_zero && !(-4..9).contains(&exponent) => (scientific, None),
+                _ => (formatted.fixed(precision), None),
+            }
+        }
+        Notation::Engineering => match formatted.engineering(precision, i32::MIN, i32::MAX) {
+            Some((mantissa, exponent)) => (format!("{}e{}", mantissa, exponent), None),
+            None => (formatted.fixed(precision), None),
+        },
+        Notation::SiPrefix { .. } => {
+            let (mantissa, exponent) = formatted
+                .engineering(
+                    precision,
+                    notation::SI_MIN_EXPONENT,
+                    notation::SI_MAX_EXPONENT,
+                )
+                .unwrap_or_else(|| (formatted.fixed(precision), 0));
+            (mantissa, Some(notation::si_prefix(exponent)))
+        }
+    };
+    if formatted.failed.get() {
+        Err(fmt::Error)
+    } else {
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// A list of numbers that are formatted according to the [`AlignOptions`] and are
+/// ready to be split into [`FractionParts`] for the alignment.
+#[derive(Debug)]
+pub(crate) struct FormattedNumbers {
+    strings: Vec<String>,
+    /// SI prefixes and units, empty for other notations.
+    suffixes: Vec<String>,
+    options: AlignOptions,
+}
+
+#[cfg(feature = "alloc")]
+impl FormattedNumbers {
+    pub(crate) fn new<T: AlignableNumber>(
+        numbers: &[T],
+        precision: FormatPrecision,
+        options: &AlignOptions,
+    ) -> Self {
+        let formatted = numbers
+            .iter()
+            .map(|number| {
+                // a number that fails to format is an empty entry, see AlignableNumber
+                format_number(number, precision, options.notation).unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        // SI prefixes and units are aligned in their own column
+        let suffixes = match options.notation {
+            Notation::SiPrefix { unit } => {
+                let has_prefixes = formatted
+                    .iter()
+                    .any(|(_, prefix)| !prefix.unwrap_or("").is_empty());
+                formatted
+                    .iter()
+                    .map(|(_, prefix)| {
+                        prefix.map_or_else(String::new, |prefix| {
+                            write_to_string(|suffix| {
+                                write_si_suffix(prefix, unit, has_prefixes, options, suffix)
+                            })
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            }
+            _ => vec![String::new(); formatted.len()],
+        };
+
+        Self {
+            strings: formatted.into_iter().map(|(string, _)| string).collect(),
+            suffixes,
+            options: options.for_precision(precision),
+        }
+    }
+
+    /// Returns the parts of all formatted numbers.
+    pub(crate) fn parts(&self) -> impl Iterator<Item = FractionParts<'_>> + Clone {
+        self.strings
+            .iter()
+            .zip(self.suffixes.iter())
+            .map(|(string, suffix)| parts_with_suffix(string, suffix))
+    }
+
+    /// Returns the options for the alignment. With [`FormatPrecision::Exact`],
+    /// unnecessary zeroes are kept.
+    pub(crate) const fn options(&self) -> &AlignOptions {
+        &self.options
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Returns the exponent of a formatted number in scientific notation, or `None` if it
+/// is not finite.
+fn scientific_exponent(scientific: &str) -> Option<i32> {
+    if scientific.contains('e') {
+        Some(notation::scientific_exponent(scientific))
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Helper to format an [`AlignableNumber`] including its sign into a [`String`].
+struct Formatter<'a, T: ?Sized> {
+    number: &'a T,
+    /// Whether the [`AlignableNumber`] implementation failed at least once.
+    failed: Cell<bool>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: AlignableNumber + ?Sized> Formatter<'a, T> {
+    fn new(number: &'a T) -> Self {
+        Self {
+            number,
+            failed: Cell::new(false),
+        }
+    }
+
+    fn sign(&self) -> &'static str {
+        if self.number.is_negative() {
+            "-"
+        } else {
+            ""
+        }
+    }
+
+    fn fixed(&self, precision: usize) -> String {
+        self.write(|string| self.number.write_fixed(precision, string))
+    }
+
+    fn scientific(&self, precision: usize) -> String {
+        self.write(|string| self.number.write_scientific(precision, string))
+    }
+
+    /// Returns the sign followed by what `write` writes and records a failure.
+    fn write(&self, write: impl FnOnce(&mut String) -> fmt::Result) -> String {
+        let mut string = String::from(self.sign());
+        if write(&mut string).is_err() {
+            self.failed.set(true);
+        }
+        string
+    }
+
+    /// Formats the number in engineering notation. Returns `None` for zero and
+    /// non-finite values, as they have no exponent.
+    fn engineering(
+        &self,
+        precision: usize,
+        min_exponent: i32,
+        max_exponent: i32,
+    ) -> Option<(String, i32)> {
+        let scientific = self.scientific(0);
+        let exponent = scientific_exponent(&scientific)?;
+        if !scientific.bytes().any(|b| (b'1'..=b'9').contains(&b)) {
+            return None;
+        }
+        Some(notation::format_engineering(
+            exponent,
+            &|precision| self.scientific(precision),
+            &|precision| self.fixed(precision),
+            precision,
+            min_exponent,
+            max_exponent,
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+
+    use super::*;
+
+    fn fixed<T: AlignableNumber>(number: T, precision: usize) -> String {
+        Formatter::new(&number).fixed(precision)
+    }
+
+    fn scientific<T: AlignableNumber>(number: T, precision: usize) -> String {
+        Formatter::new(&number).scientific(precision)
+    }
+
+    #[test]
+    fn test_primitive_types() {
+        assert_eq!("-1.50", fixed(-1.5_f32, 2));
+        assert_eq!("-0", fixed(-0.0_f64, 0));
+        assert_eq!("NaN", fixed(-f64::NAN, 2));
+        assert_eq!("-inf", fixed(f64::NEG_INFINITY, 2));
+        assert_eq!("-128.0", fixed(i8::MIN, 1));
+        assert_eq!(
+            "340282366920938463463374607431768211455",
+            fixed(u128::MAX, 0)
+        );
+
+        assert_eq!("-1.5e-7", scientific(-0.000_000_15_f64, 1));
+        assert_eq!("-1.28e2", scientific(i8::MIN, 2));
+        assert_eq!("1.8e19", scientific(u64::MAX, 1));
+    }
+
+    #[test]
+    fn test_format_number() {
+        let f = |number: f64, notation| {
+            format_number(&number, FormatPrecision::Max(2), notation).unwrap()
+        };
+        assert_eq!(("1.50".to_string(), None), f(1.5, Notation::Auto));
+        assert_eq!(("0.00".to_string(), None), f(0.0, Notation::Auto));
+        assert_eq!(("1.00e9".to_string(), None), f(1e9, Notation::Auto));
+        assert_eq!(("-1.00e-5".to_string(), None), f(-1e-5, Notation::Auto));
+        assert_eq!(
+            ("47.00e3".to_string(), None),
+            f(47e3, Notation::Engineering)
+        );
+        assert_eq!(
+            ("inf".to_string(), None),
+            f(f64::INFINITY, Notation::Engineering)
+        );
+        assert_eq!(
+            ("-3.30".to_string(), Some("n")),
+            f(-3.3e-9, Notation::SiPrefix { unit: "F" })
+        );
+    }
+}
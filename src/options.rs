@@ -0,0 +1,277 @@
+//! Options to customize the alignment, see [`AlignOptions`].
+
+use crate::{FormatPrecision, Grouping, Locale};
+
+/// A space that is as wide as a digit in fonts with tabular figures.
+pub(crate) const FIGURE_SPACE: char = '\u{2007}';
+/// A space that is as wide as a `.`.
+pub(crate) const PUNCTUATION_SPACE: char = '\u{2008}';
+/// The typographic minus sign, which is as wide as a `+`.
+pub(crate) const MINUS_SIGN: char = '\u{2212}';
+
+/// How signs of the numbers are rendered.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SignMode {
+    /// Keep the sign as it is in the input. Formatted numbers only have a sign
+    /// if they are negative.
+    Keep,
+    /// Always print a sign, i.e. `+` for all numbers that are not negative.
+    Always,
+    /// Only print the sign of negative numbers, i.e. remove a leading `+`.
+    NegativeOnly,
+}
+
+/// The characters that are used for padding and signs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutputStyle {
+    /// Padding with the padding character and the ASCII hyphen-minus `-` as minus
+    /// sign. Aligned in monospace fonts.
+    Plain,
+    /// Padding with FIGURE SPACE (`U+2007`), which is as wide as a digit, and with
+    /// PUNCTUATION SPACE (`U+2008`), which is as wide as a `.`, where a decimal
+    /// separator or grouping separator is missing. Negative numbers get the MINUS SIGN
+    /// (`U+2212`). Aligned in all fonts with tabular figures, also proportional ones,
+    /// e.g. in PDF or HTML. The padding character is ignored.
+    Typographic,
+}
+
+/// How entries are rendered that don't fit into a fixed [`crate::AlignmentLayout`].
+///
+/// This concerns layouts that were computed without the entries, e.g. with
+/// [`crate::AlignmentLayout::from_bounds`] or from a previous batch. Entries that are
+/// part of the layout always fit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Overflow {
+    /// The layout is widened. [`crate::AlignmentLayout::align`] widens the layout of
+    /// the whole batch, which is then aligned in itself but no longer with other
+    /// batches. A single entry of [`crate::AlignmentLayout::display`] is rendered
+    /// wider and breaks the alignment.
+    Widen,
+    /// Fractional digits that don't fit are cut off without rounding, e.g. `1.2345`
+    /// becomes `1.23` with two fractional digits. Entries with a whole part or suffix
+    /// that doesn't fit are marked like with [`Overflow::Mark`], as cutting them off
+    /// would change the value.
+    Truncate,
+    /// The entry is replaced by `#` in the full width of the layout, like in
+    /// spreadsheets.
+    Mark,
+}
+
+/// The notation in which [`crate::fmt_align_fractions_with`] formats the numbers.
+///
+/// Numbers in scientific notation are aligned on the decimal point of the mantissa
+/// and on the `e` of the exponent. This has no effect on string input, as strings in
+/// scientific notation, such as `1.5e-7`, are recognized anyway.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Notation {
+    /// Plain decimal notation, e.g. `1234.5`.
+    Fixed,
+    /// Scientific notation, e.g. `1.2345e3`. The precision applies to the mantissa.
+    Scientific,
+    /// Scientific notation for very large or tiny values, i.e. if the absolute value
+    /// is at least `1e9` or less than `1e-4` (zero excluded), and plain decimal
+    /// notation for all other values.
+    Auto,
+    /// Engineering notation, i.e. scientific notation with an exponent that is a
+    /// multiple of three, e.g. `47e3` or `3.3e-9`. The precision applies to the mantissa.
+    Engineering,
+    /// Engineering notation with SI prefixes (`p`, `n`, `µ`, `m`, `k`, `M`, `G`, `T`)
+    /// instead of exponents, followed by a unit, e.g. `47 kΩ` or `3.3 nF`. The
+    /// prefixes and units are aligned in their own column. Values out of the range
+    /// of the prefixes get the smallest or largest prefix. The precision applies to
+    /// the mantissa.
+    SiPrefix {
+        /// The unit after the prefix, e.g. `Ω`. Might be empty.
+        unit: &'static str,
+    },
+}
+
+/// Options for [`crate::fmt_align_fraction_strings_with`] and
+/// [`crate::fmt_align_fractions_with`].
+///
+/// The default options reproduce the behavior of [`crate::fmt_align_fraction_strings`].
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// use fraction_list_fmt_align::{fmt_align_fraction_strings_with, AlignOptions};
+///
+/// let options = AlignOptions::new()
+///     .padding_char('_')
+///     .right_pad(false)
+///     .decimal_separator(',');
+/// let aligned = fmt_align_fraction_strings_with(&["-42", "0,3214", "1000"], &options);
+/// assert_eq!(aligned, ["_-42", "___0,3214", "1000"]);
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AlignOptions {
+    pub(crate) padding_char: char,
+    pub(crate) right_pad: bool,
+    pub(crate) strip_trailing_zeroes: bool,
+    pub(crate) zero_pad_fraction: bool,
+    pub(crate) input_locale: Locale,
+    pub(crate) output_locale: Locale,
+    pub(crate) min_width: usize,
+    pub(crate) sign_mode: SignMode,
+    pub(crate) notation: Notation,
+    pub(crate) output_style: OutputStyle,
+    pub(crate) overflow: Overflow,
+}
+
+impl AlignOptions {
+    /// Creates the default options: padding with spaces on both sides, unnecessary
+    /// zeroes are removed and `.` is the decimal separator.
+    pub const fn new() -> Self {
+        Self {
+            padding_char: ' ',
+            right_pad: true,
+            strip_trailing_zeroes: true,
+            zero_pad_fraction: false,
+            input_locale: Locale::POSIX,
+            output_locale: Locale::POSIX,
+            min_width: 0,
+            sign_mode: SignMode::Keep,
+            notation: Notation::Fixed,
+            output_style: OutputStyle::Plain,
+            overflow: Overflow::Widen,
+        }
+    }
+
+    /// Sets the character used for padding. Default is `' '`. Has no effect with
+    /// [`OutputStyle::Typographic`].
+    pub const fn padding_char(mut self, padding_char: char) -> Self {
+        self.padding_char = padding_char;
+        self
+    }
+
+    /// Sets whether entries are padded on the right so that all of them have the same
+    /// width. Default is `true`.
+    pub const fn right_pad(mut self, right_pad: bool) -> Self {
+        self.right_pad = right_pad;
+        self
+    }
+
+    /// Sets whether unnecessary zeroes are removed from the fractional parts, i.e.
+    /// `2.500` becomes `2.5`. Default is `true`.
+    pub const fn strip_trailing_zeroes(mut self, strip_trailing_zeroes: bool) -> Self {
+        self.strip_trailing_zeroes = strip_trailing_zeroes;
+        self
+    }
+
+    /// Sets whether fractional parts are padded with zeroes to the widest fractional
+    /// part instead of with the padding character, i.e. `2.5` becomes `2.500` if
+    /// another entry has three decimal places. Default is `false`.
+    pub const fn zero_pad_fraction(mut self, zero_pad_fraction: bool) -> Self {
+        self.zero_pad_fraction = zero_pad_fraction;
+        self
+    }
+
+    /// Sets the decimal separator that is used for parsing and rendering.
+    /// Default is `'.'`.
+    pub const fn decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.input_locale.decimal_separator = decimal_separator;
+        self.output_locale.decimal_separator = decimal_separator;
+        self
+    }
+
+    /// Sets the separator that is inserted into the whole part according to the
+    /// [`Grouping`], by default every three digits, e.g. `,` for `1,234,567.89`. Common
+    /// choices are `,`, `.`, `'`, `_`, or a thin space (`'\u{2009}'`). The separators
+    /// of all entries are aligned. Grouping separators that are already present in
+    /// string input are replaced. A separator that is the same as the decimal separator
+    /// is ignored, as the numbers could not be read back. Default is `None`.
+    pub const fn grouping_separator(mut self, grouping_separator: Option<char>) -> Self {
+        self.input_locale.grouping_separator = grouping_separator;
+        self.output_locale.grouping_separator = grouping_separator;
+        self
+    }
+
+    /// Sets the [`Grouping`] that is used for parsing and rendering, e.g.
+    /// [`Grouping::Indian`] for `12,34,56,789`. Only has an effect with a grouping
+    /// separator. Default is `Grouping::Fixed(3)`.
+    pub const fn grouping(mut self, grouping: Grouping) -> Self {
+        self.input_locale.grouping = grouping;
+        self.output_locale.grouping = grouping;
+        self
+    }
+
+    /// Sets the [`Locale`] that is used for parsing and rendering. This overrides
+    /// [`Self::decimal_separator`] and [`Self::grouping_separator`]. Default is
+    /// [`Locale::POSIX`].
+    pub const fn locale(mut self, locale: Locale) -> Self {
+        self.input_locale = locale;
+        self.output_locale = locale;
+        self
+    }
+
+    /// Sets the [`Locale`] that is used for parsing string input. Grouping separators
+    /// of the input locale are ignored. Default is [`Locale::POSIX`].
+    pub const fn input_locale(mut self, locale: Locale) -> Self {
+        self.input_locale = locale;
+        self
+    }
+
+    /// Sets the [`Locale`] that is used for rendering. Default is [`Locale::POSIX`].
+    pub const fn output_locale(mut self, locale: Locale) -> Self {
+        self.output_locale = locale;
+        self
+    }
+
+    /// Sets the minimum display width of all entries. If the aligned entries are
+    /// narrower, they are padded on the left. Default is `0`.
+    pub const fn min_width(mut self, min_width: usize) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    /// Sets how signs are rendered. Default is [`SignMode::Keep`].
+    pub const fn sign_mode(mut self, sign_mode: SignMode) -> Self {
+        self.sign_mode = sign_mode;
+        self
+    }
+
+    /// Sets the notation of formatted numbers. Default is [`Notation::Fixed`].
+    pub const fn notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    /// Sets the characters that are used for padding and signs. Default is
+    /// [`OutputStyle::Plain`].
+    pub const fn output_style(mut self, output_style: OutputStyle) -> Self {
+        self.output_style = output_style;
+        self
+    }
+
+    /// Sets how entries are rendered that don't fit into a fixed layout. Default is
+    /// [`Overflow::Widen`].
+    pub const fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Returns the character that is used for padding, which depends on the
+    /// [`OutputStyle`].
+    pub(crate) const fn effective_padding_char(&self) -> char {
+        match self.output_style {
+            OutputStyle::Plain => self.padding_char,
+            OutputStyle::Typographic => FIGURE_SPACE,
+        }
+    }
+
+    /// Returns the options for numbers that are formatted with the precision. With
+    /// [`FormatPrecision::Exact`], unnecessary zeroes are kept.
+    pub(crate) const fn for_precision(self, precision: FormatPrecision) -> Self {
+        match precision {
+            FormatPrecision::Exact(_) => self.strip_trailing_zeroes(false),
+            FormatPrecision::Max(_) => self,
+        }
+    }
+}
+
+impl Default for AlignOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
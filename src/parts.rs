@@ -0,0 +1,335 @@
+//! Splitting of formatted fraction number strings into their parts.
+
+#[cfg(feature = "alloc")]
+use crate::{Grouping, InvalidNumberReason, Locale};
+
+/// A formatted fraction number string split into its parts. For example, `-10.1234`
+/// consists of the sign `-`, the whole part `10` and the fractional part `1234`.
+/// `1.5e-7` consists of the whole part `1`, the fractional part `5` and the
+/// suffix `e-7`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct FractionParts<'a> {
+    /// Either `-`, `+` or empty.
+    pub(crate) sign: &'a str,
+    /// The whole part without the sign. For non-finite values, this is `NaN` or `inf`.
+    pub(crate) whole: &'a str,
+    /// The fractional part without the decimal separator. `None` if there is no
+    /// decimal separator.
+    pub(crate) fraction: Option<&'a str>,
+    /// The exponent including the leading `e` or `E`, e.g. `e-7`, or an SI prefix
+    /// and unit, e.g. ` kΩ`. `None` if there is neither.
+    pub(crate) suffix: Option<&'a str>,
+}
+
+impl<'a> FractionParts<'a> {
+    /// Splits a formatted fraction number string into its parts without validating it.
+    /// * `123` => `("", "123", None)`
+    /// * `-10.1234` => `("-", "10", Some("1234"))`
+    /// * `1.` => `("", "1", Some(""))`
+    /// * `6.02E23` => `("", "6", Some("02"), Some("E23"))`
+    pub(crate) fn parse(string: &'a str, decimal_separator: char) -> Self {
+        let (sign, string) = split_sign(string);
+        let (mantissa, exponent) = split_exponent(string);
+        Self {
+            sign,
+            whole: get_whole_part(mantissa, decimal_separator),
+            fraction: get_fractional_part(mantissa, decimal_separator),
+            suffix: exponent,
+        }
+    }
+
+    /// Like [`Self::parse`] but checks that the string is a valid fraction number in
+    /// the given locale. Grouping separators are valid in the whole part only, and
+    /// if there are any, they must be at exactly the positions of the grouping.
+    /// * `-10.1234` => `Ok`
+    /// * `1.2.3` => `Err(MultipleDecimalPoints)`
+    /// * `12 ` => `Err(InvalidCharacter(' '))`
+    /// * `1-2` => `Err(MisplacedSign)`
+    /// * `1,,2` => `Err(MisplacedGroupingSeparator)` (English)
+    /// * `1e2.5` => `Err(InvalidExponent)`
+    ///
+    /// The non-finite values `NaN`, `inf`, `-inf` and `+inf` are valid, as they are
+    /// produced by formatting floating point values.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn try_parse(string: &'a str, locale: &Locale) -> Result<Self, InvalidNumberReason> {
+        let decimal_separator = locale.decimal_separator;
+        if string.is_empty() {
+            return Err(InvalidNumberReason::Empty);
+        }
+        if matches!(string, "NaN" | "inf" | "-inf" | "+inf") {
+            return Ok(Self::parse(string, decimal_separator));
+        }
+
+        let (mantissa, exponent) = split_exponent(string);
+        let mut has_digits = false;
+        let mut has_decimal_separator = false;
+        for (index, char) in mantissa.chars().enumerate() {
+            match char {
+                '0'..='9' => has_digits = true,
+                c if c == decimal_separator && has_decimal_separator => {
+                    return Err(InvalidNumberReason::MultipleDecimalPoints)
+                }
+                c if c == decimal_separator => has_decimal_separator = true,
+                c if Some(c) == locale.grouping_separator() && !has_decimal_separator => {}
+                '-' | '+' if index == 0 => {}
+                '-' | '+' => return Err(InvalidNumberReason::MisplacedSign),
+                _ => return Err(InvalidNumberReason::InvalidCharacter(char)),
+            }
+        }
+
+        if !has_digits {
+            return Err(InvalidNumberReason::NoDigits);
+        }
+
+        if let Some(separator) = locale.grouping_separator() {
+            let whole = get_whole_part(split_sign(mantissa).1, decimal_separator);
+            if whole.contains(separator) && !is_grouped(whole, separator, locale.grouping) {
+                return Err(InvalidNumberReason::MisplacedGroupingSeparator);
+            }
+        }
+
+        if let Some(exponent) = exponent {
+            // skip the "e" or "E"
+            let (_sign, digits) = split_sign(&exponent[1..]);
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(InvalidNumberReason::InvalidExponent);
+            }
+        }
+
+        Ok(Self::parse(string, decimal_separator))
+    }
+
+    /// Removes unnecessary zeroes from the fractional part. This means:
+    /// * `123` => `123`
+    /// * `123.13` => `123.13`
+    /// * `0.1234000` => `0.1234`
+    /// * `-10.000000` => `-10`
+    pub(crate) fn strip_trailing_zeroes(self) -> Self {
+        let fraction = self.fraction.and_then(|fraction| {
+            let zeroes = fraction_part_count_zeroes(fraction);
+            if fraction.len() == zeroes {
+                None
+            } else {
+                Some(&fraction[0..fraction.len() - zeroes])
+            }
+        });
+        Self { fraction, ..self }
+    }
+
+    /// Whether the parts describe a finite number, i.e. not `NaN` or `inf`.
+    pub(crate) fn is_finite(&self) -> bool {
+        self.whole
+            .bytes()
+            .chain(self.fraction.unwrap_or("").bytes())
+            .any(|b| b.is_ascii_digit())
+    }
+}
+
+/// Splits a leading `-` or `+` from a formatted fraction number string.
+/// * `-10.1234` => `("-", "10.1234")`
+/// * `10.1234` => `("", "10.1234")`
+fn split_sign(string: &str) -> (&str, &str) {
+    if string.starts_with('-') || string.starts_with('+') {
+        string.split_at(1)
+    } else {
+        ("", string)
+    }
+}
+
+/// Splits the exponent including the leading `e` or `E` from a formatted fraction
+/// number string.
+/// * `1.5e-7` => `("1.5", Some("e-7"))`
+/// * `1.5` => `("1.5", None)`
+fn split_exponent(string: &str) -> (&str, Option<&str>) {
+    string
+        .find(&['e', 'E'][..])
+        .map_or((string, None), |index| {
+            let (mantissa, exponent) = string.split_at(index);
+            (mantissa, Some(exponent))
+        })
+}
+
+/// Get the whole part (TODO is this the right term?)
+/// from a formatted fraction number string.
+/// * `123` => `123`
+/// * `123.13` => `123`
+/// * `0.1234` => `0`
+/// * `-10.1234` => `-10`
+fn get_whole_part(string: &str, decimal_separator: char) -> &str {
+    // if it doesn't contain the separator the whole thing is returned
+    string.split(decimal_separator).next().unwrap()
+}
+
+/// Get the fractional part from a formatted fraction number string.
+/// * `123` => `None`
+/// * `123.13` => `Some(13)`
+/// * `0.1234` => `Some(1234)`
+/// * `-10.1234` => `Some(1234)`
+fn get_fractional_part(string: &str, decimal_separator: char) -> Option<&str> {
+    string
+        .split_once(decimal_separator)
+        .map(|(_whole_part, fraction_part)| fraction_part)
+}
+
+#[cfg(feature = "alloc")]
+/// Whether the grouping separators of a whole part of digits and separators are at
+/// exactly the positions of the grouping.
+/// * `1,234,567` => `true`
+/// * `1,,2`, `,123`, `123,` => `false`
+/// * `12,34`, `1234,567` => `false` (groups of three)
+fn is_grouped(whole: &str, separator: char, grouping: Grouping) -> bool {
+    let count = whole.chars().filter(char::is_ascii_digit).count();
+    let expected = whole
+        .chars()
+        .filter(char::is_ascii_digit)
+        .enumerate()
+        .flat_map(|(index, digit)| {
+            let has_separator = index > 0 && grouping.is_separator_position(count - index);
+            has_separator
+                .then(|| separator)
+                .into_iter()
+                .chain(Some(digit))
+        });
+    whole.chars().eq(expected)
+}
+
+/// Takes only the fraction part of a string without ".".
+/// Counts that in "123000" (fractional part of "0.123000") are three unnecessary zeroes.
+/// In "0.0000" there are four unnecessary zeroes. As `0` is ASCII, this is the number
+/// of characters as well as the number of bytes.
+fn fraction_part_count_zeroes(fraction_part: &str) -> usize {
+    fraction_part
+        .bytes()
+        .rev()
+        .take_while(|byte| *byte == b'0')
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_fraction_part_count_zeroes() {
+        assert_eq!(3, fraction_part_count_zeroes("123000"));
+        assert_eq!(0, fraction_part_count_zeroes("123"));
+        assert_eq!(1, fraction_part_count_zeroes("0"));
+        assert_eq!(11, fraction_part_count_zeroes("00000012800000000000"));
+        assert_eq!(2, fraction_part_count_zeroes("５µ00"));
+        assert_eq!(0, fraction_part_count_zeroes("００"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let parts = FractionParts::parse("-10.1234", '.');
+        assert_eq!(
+            ("-", "10", Some("1234")),
+            (parts.sign, parts.whole, parts.fraction)
+        );
+        let parts = FractionParts::parse("1000", '.');
+        assert_eq!(
+            ("", "1000", None),
+            (parts.sign, parts.whole, parts.fraction)
+        );
+        let parts = FractionParts::parse("+3,14", ',');
+        assert_eq!(
+            ("+", "3", Some("14")),
+            (parts.sign, parts.whole, parts.fraction)
+        );
+
+        let parts = FractionParts::parse("-10.000", '.').strip_trailing_zeroes();
+        assert_eq!(("-", "10", None), (parts.sign, parts.whole, parts.fraction));
+
+        let parts = FractionParts::parse("-1.500e-7", '.').strip_trailing_zeroes();
+        assert_eq!(
+            ("-", "1", Some("5"), Some("e-7")),
+            (parts.sign, parts.whole, parts.fraction, parts.suffix)
+        );
+        let parts = FractionParts::parse("6.02E23", '.');
+        assert_eq!(
+            ("", "6", Some("02"), Some("E23")),
+            (parts.sign, parts.whole, parts.fraction, parts.suffix)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_try_parse() {
+        assert!(FractionParts::try_parse("-10.1234", &Locale::POSIX).is_ok());
+        assert!(FractionParts::try_parse(".5", &Locale::POSIX).is_ok());
+        assert!(FractionParts::try_parse("-inf", &Locale::POSIX).is_ok());
+        assert!(FractionParts::try_parse("3,5", &Locale::new(',', None)).is_ok());
+        assert!(FractionParts::try_parse("1.5e-7", &Locale::POSIX).is_ok());
+        assert!(FractionParts::try_parse("6.02E+23", &Locale::POSIX).is_ok());
+        assert!(FractionParts::try_parse("-1.000.000,5", &Locale::DE).is_ok());
+        assert_eq!(
+            Err(InvalidNumberReason::Empty),
+            FractionParts::try_parse("", &Locale::POSIX)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::MultipleDecimalPoints),
+            FractionParts::try_parse("1.2.3", &Locale::POSIX)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::InvalidCharacter('a')),
+            FractionParts::try_parse("abc", &Locale::POSIX)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::InvalidCharacter(' ')),
+            FractionParts::try_parse("12 ", &Locale::POSIX)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::InvalidCharacter('.')),
+            FractionParts::try_parse("3.5", &Locale::new(',', None))
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::InvalidCharacter(',')),
+            FractionParts::try_parse("1.000,5", &Locale::POSIX)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::InvalidCharacter('.')),
+            FractionParts::try_parse("1,5.5", &Locale::DE)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::MisplacedSign),
+            FractionParts::try_parse("1-2", &Locale::POSIX)
+        );
+        assert!(FractionParts::try_parse("12,34,567.5", &Locale::IN).is_ok());
+        assert!(FractionParts::try_parse("+1'000", &Locale::CH).is_ok());
+        for (input, locale) in [
+            ("1.2.3,4", Locale::DE),
+            ("1,,2", Locale::EN),
+            (",123", Locale::EN),
+            ("-,123", Locale::EN),
+            ("123,", Locale::EN),
+            ("123,.5", Locale::EN),
+            ("12,34", Locale::EN),
+            ("1234,567", Locale::EN),
+            ("1,234,567", Locale::IN),
+        ] {
+            assert_eq!(
+                Err(InvalidNumberReason::MisplacedGroupingSeparator),
+                FractionParts::try_parse(input, &locale),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            Err(InvalidNumberReason::NoDigits),
+            FractionParts::try_parse("-.", &Locale::POSIX)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::NoDigits),
+            FractionParts::try_parse("e5", &Locale::POSIX)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::InvalidExponent),
+            FractionParts::try_parse("1e", &Locale::POSIX)
+        );
+        assert_eq!(
+            Err(InvalidNumberReason::InvalidExponent),
+            FractionParts::try_parse("1e2.5", &Locale::POSIX)
+        );
+    }
+}
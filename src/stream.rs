@@ -0,0 +1,444 @@
+//! Alignment of values that arrive one at a time, see [`StreamingAligner`].
+
+use crate::align::{AlignedEntry, AlignmentLayout};
+use crate::number::BufferedNumber;
+use crate::parts::FractionParts;
+use crate::{AlignOptions, AlignableNumber, FormatPrecision, Notation};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Aligns values that arrive one at a time, e.g. a measurement per second of a
+/// long-running tool, for which the full list is not known up front.
+///
+/// Every pushed value is rendered immediately with the widths of all values so far.
+/// If a value is wider than all values before, e.g. the first value with a
+/// fractional part or with more digits, the layout widens and the lines that were
+/// emitted before are no longer aligned with the new ones. This is reported by
+/// [`StreamedLine::widened`]. The previous lines can then be reprinted with the new
+/// layout via [`Self::lines`] or [`Self::push_with`], or in a terminal via
+/// [`Self::write_ansi`].
+///
+/// All values are kept for that by default. For long-running streams, limit them with
+/// [`Self::history`].
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::StreamingAligner;
+///
+/// let mut aligner = StreamingAligner::new();
+/// assert_eq!(aligner.push_str("10").line(), "10");
+/// assert_eq!(aligner.push_str("2").line(), " 2");
+///
+/// let line = aligner.push_str("-3.5");
+/// assert_eq!(line.line(), "-3.5");
+/// assert!(line.widened());
+/// let lines = aligner.lines().map(|entry| entry.to_string()).collect::<Vec<_>>();
+/// assert_eq!(lines, ["10  ", " 2  ", "-3.5"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamingAligner {
+    precision: FormatPrecision,
+    options: AlignOptions,
+    layout: AlignmentLayout,
+    /// The kept values, a ring buffer of at most `history` entries.
+    entries: Vec<Entry>,
+    /// The index of the oldest entry in `entries`.
+    oldest: usize,
+    history: usize,
+    len: usize,
+}
+
+impl StreamingAligner {
+    /// Creates an aligner without values. Numbers are formatted with
+    /// `FormatPrecision::Max(6)` and the default [`AlignOptions`].
+    pub const fn new() -> Self {
+        let precision = FormatPrecision::Max(6);
+        let options = AlignOptions::new();
+        Self {
+            precision,
+            options,
+            layout: empty_layout(precision, options),
+            entries: Vec::new(),
+            oldest: 0,
+            history: usize::MAX,
+            len: 0,
+        }
+    }
+
+    /// Sets the precision of the numbers of [`Self::push`]. Default is
+    /// `FormatPrecision::Max(6)`. With [`FormatPrecision::Exact`], unnecessary zeroes
+    /// are kept, also of the strings of [`Self::push_str`].
+    ///
+    /// ## Panics
+    /// If a value was pushed already, as its line was formatted with the old precision.
+    pub fn precision(mut self, precision: FormatPrecision) -> Self {
+        self.assert_unused();
+        self.precision = precision;
+        self.layout = empty_layout(precision, self.options);
+        self
+    }
+
+    /// Sets the [`AlignOptions`].
+    ///
+    /// ## Panics
+    /// If a value was pushed already, as its line was formatted with the old options.
+    pub fn options(mut self, options: AlignOptions) -> Self {
+        self.assert_unused();
+        self.options = options;
+        self.layout = empty_layout(self.precision, options);
+        self
+    }
+
+    /// Sets the number of values that are kept for [`Self::lines`], [`Self::push_with`]
+    /// and [`Self::write_ansi`], so that the memory doesn't grow with the stream.
+    /// Older values are dropped, but they still contribute to the layout. Default is
+    /// `usize::MAX`, i.e. all values are kept.
+    ///
+    /// ## Panics
+    /// If a value was pushed already.
+    pub fn history(mut self, history: usize) -> Self {
+        self.assert_unused();
+        self.history = history;
+        self
+    }
+
+    /// Formats the number and returns its aligned line, see [`StreamedLine`].
+    ///
+    /// With [`Notation::SiPrefix`] and a unit, the column of the SI prefix is always
+    /// reserved, as any later value may have a prefix. Values without a prefix are
+    /// padded there, so that the units are aligned.
+    ///
+    /// Like in [`crate::fmt_align_numbers_with`], a number whose [`AlignableNumber`]
+    /// implementation fails is an empty entry.
+    pub fn push<T: AlignableNumber>(&mut self, number: T) -> StreamedLine {
+        let reserve_prefix = match self.options.notation {
+            Notation::SiPrefix { unit } => !unit.is_empty(),
+            _ => false,
+        };
+        let formatted = BufferedNumber::new(&number, self.precision, &self.options, reserve_prefix)
+            .unwrap_or_default();
+        self.push_entry(Entry::new(formatted.parts()))
+    }
+
+    /// Adds a formatted fraction number string, such as the input of
+    /// [`crate::fmt_align_fraction_strings_with`], and returns its aligned line, see
+    /// [`StreamedLine`]. The string is not validated.
+    pub fn push_str(&mut self, string: &str) -> StreamedLine {
+        let decimal_separator = self.options.input_locale.decimal_separator;
+        self.push_entry(Entry::new(FractionParts::parse(string, decimal_separator)))
+    }
+
+    /// Like [`Self::push`] but calls `reprint` with the index and the new rendering of
+    /// every previous line that is kept, see [`Self::history`], if the layout widened.
+    pub fn push_with<T: AlignableNumber>(
+        &mut self,
+        number: T,
+        mut reprint: impl FnMut(usize, AlignedEntry<'_>),
+    ) -> StreamedLine {
+        let line = self.push(number);
+        if line.widened {
+            let previous = self.previous_len();
+            let first = self.len - 1 - previous;
+            self.lines()
+                .take(previous)
+                .enumerate()
+                .for_each(|(index, entry)| reprint(first + index, entry));
+        }
+        line
+    }
+
+    /// Returns the current layout, i.e. the widths of all values so far.
+    pub const fn layout(&self) -> &AlignmentLayout {
+        &self.layout
+    }
+
+    /// Returns the number of values so far, including the ones that are no longer kept.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no value was pushed so far.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the renderings of the kept values, see [`Self::history`], with the
+    /// current layout, which implement [`fmt::Display`]. Unlike the lines that were
+    /// emitted while the layout was narrower, these are all aligned.
+    pub fn lines(&self) -> impl Iterator<Item = AlignedEntry<'_>> + '_ {
+        let (newer, older) = self.entries.split_at(self.oldest);
+        older
+            .iter()
+            .chain(newer)
+            .map(move |entry| self.layout.display_parts(entry.parts()))
+    }
+
+    /// Writes the line of the last pushed value followed by a line break for a
+    /// terminal. If the layout widened, the previous lines are reprinted first with
+    /// ANSI escape sequences that move the cursor up and clear every line.
+    ///
+    /// This assumes that every previous line was written with this function directly
+    /// above the cursor and that no other output came in between. Lines that were
+    /// scrolled out of the terminal or that are no longer kept, see [`Self::history`],
+    /// can't be reprinted.
+    pub fn write_ansi<W: fmt::Write + ?Sized>(
+        &self,
+        out: &mut W,
+        line: &StreamedLine,
+    ) -> fmt::Result {
+        let previous = self.previous_len();
+        if line.widened && previous > 0 {
+            // cursor up to the first line
+            write!(out, "\x1b[{}A", previous)?;
+            for entry in self.lines().take(previous) {
+                // clear the line and rewrite it
+                write!(out, "\r\x1b[2K{}\n", entry)?;
+            }
+        }
+        writeln!(out, "{}", line.line)
+    }
+
+    fn push_entry(&mut self, entry: Entry) -> StreamedLine {
+        let layout = self.layout.with_parts(entry.parts());
+        let widened = layout != self.layout && !self.is_empty();
+        self.layout = layout;
+        let line = self.layout.display_parts(entry.parts()).to_string();
+        self.len += 1;
+        if self.entries.len() < self.history {
+            self.entries.push(entry);
+        } else if self.history > 0 {
+            self.entries[self.oldest] = entry;
+            self.oldest = (self.oldest + 1) % self.history;
+        }
+        StreamedLine { line, widened }
+    }
+
+    /// Returns the number of kept values before the last pushed one.
+    fn previous_len(&self) -> usize {
+        self.entries
+            .len()
+            .saturating_sub(usize::from(self.history > 0))
+    }
+
+    fn assert_unused(&self) {
+        assert!(
+            self.is_empty(),
+            "the aligner must be configured before the first value is pushed"
+        );
+    }
+}
+
+impl Default for StreamingAligner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The aligned line of a value of a [`StreamingAligner`]. It is aligned with all
+/// previous values, but the previous lines are only aligned with it if the layout
+/// didn't widen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamedLine {
+    line: String,
+    widened: bool,
+}
+
+impl StreamedLine {
+    /// Returns the aligned line.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Whether the value widened the layout, so that the lines of the previous values
+    /// must be reprinted to be aligned with this one. Always `false` for the first
+    /// value.
+    pub const fn widened(&self) -> bool {
+        self.widened
+    }
+
+    /// Returns the aligned line as `String`.
+    pub fn into_string(self) -> String {
+        self.line
+    }
+}
+
+impl fmt::Display for StreamedLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.line)
+    }
+}
+
+/// The owned [`FractionParts`] of a value.
+#[derive(Debug, Clone)]
+struct Entry {
+    sign: String,
+    whole: String,
+    fraction: Option<String>,
+    suffix: Option<String>,
+}
+
+impl Entry {
+    fn new(parts: FractionParts) -> Self {
+        Self {
+            sign: parts.sign.to_string(),
+            whole: parts.whole.to_string(),
+            fraction: parts.fraction.map(ToString::to_string),
+            suffix: parts.suffix.map(ToString::to_string),
+        }
+    }
+
+    fn parts(&self) -> FractionParts<'_> {
+        FractionParts {
+            sign: &self.sign,
+            whole: &self.whole,
+            fraction: self.fraction.as_deref(),
+            suffix: self.suffix.as_deref(),
+        }
+    }
+}
+
+/// Returns the layout without values. With [`FormatPrecision::Exact`], unnecessary
+/// zeroes are kept, like in [`crate::fmt_align_numbers_with`].
+const fn empty_layout(precision: FormatPrecision, options: AlignOptions) -> AlignmentLayout {
+    AlignmentLayout::empty(&options.for_precision(precision))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Locale;
+    use alloc::vec;
+
+    #[test]
+    fn test_streaming_aligner() {
+        let mut aligner = StreamingAligner::new().options(AlignOptions::new().right_pad(false));
+        let lines = [1000.0, -2.5, 3.25, 10.0, -12345.0]
+            .iter()
+            .map(|number| aligner.push(*number))
+            .map(|line| (line.to_string(), line.widened()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("1000".to_string(), false),
+                ("  -2.5".to_string(), true),
+                ("   3.25".to_string(), true),
+                ("  10".to_string(), false),
+                ("-12345".to_string(), true),
+            ],
+            lines
+        );
+        assert_eq!(5, aligner.len());
+        assert_eq!(
+            vec!["  1000", "    -2.5", "     3.25", "    10", "-12345"],
+            aligner
+                .lines()
+                .map(|entry| entry.to_string())
+                .collect::<Vec<_>>()
+        );
+
+        // strings in the input locale, zeroes are kept with an exact precision
+        let mut aligner = StreamingAligner::new()
+            .precision(FormatPrecision::Exact(2))
+            .options(AlignOptions::new().locale(Locale::DE));
+        assert_eq!("1,50", aligner.push_str("1,50").line());
+        assert_eq!("12,00", aligner.push(12).line());
+
+        // the units are aligned like in a batch, even if the first value has no prefix
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "Ω" });
+        let mut aligner = StreamingAligner::new().options(options);
+        assert_eq!("4.7  Ω", aligner.push(4.7).line());
+        assert!(aligner.push(47000.0).widened());
+        assert_eq!(
+            crate::fmt_align_numbers_with(&[4.7, 47000.0], FormatPrecision::Max(6), &options),
+            aligner
+                .lines()
+                .map(|entry| entry.to_string())
+                .collect::<Vec<_>>()
+        );
+
+        // without a unit, nothing is reserved, like in a batch
+        let options = AlignOptions::new().notation(Notation::SiPrefix { unit: "" });
+        let mut aligner = StreamingAligner::new().options(options);
+        assert_eq!("1.5", aligner.push(1.5).line());
+        assert_eq!(
+            crate::fmt_align_numbers_with(&[2.5], FormatPrecision::Max(6), &options),
+            vec![aligner.push(2.5).into_string()]
+        );
+        assert!(aligner.push(1500.0).widened());
+        assert_eq!(
+            crate::fmt_align_numbers_with(&[1.5, 2.5, 1500.0], FormatPrecision::Max(6), &options),
+            aligner
+                .lines()
+                .map(|entry| entry.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_streaming_aligner_history() {
+        let mut aligner = StreamingAligner::new().history(2);
+        let mut reprinted = Vec::new();
+        for number in [1, 2, 3, -40] {
+            aligner.push_with(number, |index, entry| {
+                reprinted.push((index, entry.to_string()));
+            });
+        }
+        assert_eq!(4, aligner.len());
+        assert_eq!(
+            vec!["  3", "-40"],
+            aligner
+                .lines()
+                .map(|entry| entry.to_string())
+                .collect::<Vec<_>>()
+        );
+        // only the kept line before the last one is reprinted
+        assert_eq!(vec![(2, "  3".to_string())], reprinted);
+
+        // the older values still contribute to the layout
+        let mut aligner = StreamingAligner::new().history(0);
+        let mut output = String::new();
+        for number in [-5, 7, 100] {
+            let line = aligner.push(number);
+            aligner.write_ansi(&mut output, &line).unwrap();
+        }
+        assert_eq!("-5\n 7\n100\n", output);
+        assert_eq!(0, aligner.lines().count());
+        assert!(!aligner.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "the aligner must be configured before the first value is pushed")]
+    fn test_streaming_aligner_late_options() {
+        let mut aligner = StreamingAligner::new();
+        aligner.push(1);
+        let _ = aligner.precision(FormatPrecision::Max(2));
+    }
+
+    #[test]
+    fn test_streaming_aligner_reprint() {
+        let mut aligner = StreamingAligner::new();
+        let mut reprinted = Vec::new();
+        let mut push = |aligner: &mut StreamingAligner, number: i32| {
+            aligner.push_with(number, |index, entry| {
+                reprinted.push((index, entry.to_string()));
+            })
+        };
+        push(&mut aligner, 1);
+        push(&mut aligner, 2);
+        push(&mut aligner, -30);
+        assert_eq!(
+            vec![(0, "  1".to_string()), (1, "  2".to_string())],
+            reprinted
+        );
+
+        let mut aligner = StreamingAligner::new();
+        let mut output = String::new();
+        for number in [5, 7, 100] {
+            let line = aligner.push(number);
+            aligner.write_ansi(&mut output, &line).unwrap();
+        }
+        assert_eq!("5\n7\n\x1b[2A\r\x1b[2K  5\n\r\x1b[2K  7\n100\n", output);
+    }
+}
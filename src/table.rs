@@ -0,0 +1,524 @@
+//! Tables with independently aligned columns, see [`Table`].
+
+use crate::width::display_width;
+use crate::{fmt_align_numbers_with, AlignOptions, FormatPrecision, FractionNumber};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A cell of a [`Table`].
+#[derive(Debug, Clone)]
+pub enum Cell {
+    /// A number that is aligned on the decimal point with the other numbers of its
+    /// column.
+    Number(FractionNumber),
+    /// Text that is aligned according to the [`TextAlign`] of its column.
+    Text(String),
+    /// An empty cell. Missing cells at the end of a row are empty as well.
+    Empty,
+}
+
+impl From<FractionNumber> for Cell {
+    fn from(number: FractionNumber) -> Self {
+        Self::Number(number)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($ty:ty),+) => {
+        $(
+            impl From<$ty> for Cell {
+                fn from(number: $ty) -> Self {
+                    Self::Number(FractionNumber::from(number))
+                }
+            }
+        )+
+    };
+}
+
+impl_from_number!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+/// How text cells and the header are aligned within their column.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextAlign {
+    /// Aligned to the left.
+    Left,
+    /// Aligned to the right.
+    Right,
+    /// Centered. If the padding is uneven, there is more on the right.
+    Center,
+}
+
+/// The configuration of a column of a [`Table`].
+#[derive(Debug, Clone)]
+pub struct Column {
+    header: String,
+    align: TextAlign,
+    precision: FormatPrecision,
+    options: AlignOptions,
+}
+
+impl Column {
+    /// Creates a column with the given header. An empty header means that the column
+    /// has no header. Text is aligned to the left, numbers are formatted with
+    /// `FormatPrecision::Max(6)` and the default [`AlignOptions`].
+    pub fn new(header: impl Into<String>) -> Self {
+        Self {
+            header: header.into(),
+            align: TextAlign::Left,
+            precision: FormatPrecision::Max(6),
+            options: AlignOptions::new(),
+        }
+    }
+
+    /// Sets how text cells and the header are aligned. Numbers are always aligned on
+    /// the decimal point and to the right of the column. Default is
+    /// [`TextAlign::Left`].
+    pub const fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets the precision of the numbers. Default is `FormatPrecision::Max(6)`.
+    pub const fn precision(mut self, precision: FormatPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Sets the [`AlignOptions`] of the numbers, e.g. a grouping separator or the
+    /// notation.
+    pub const fn options(mut self, options: AlignOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl Default for Column {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+/// A table of numbers and text. The numbers of every column are aligned on their
+/// decimal points independently of the other columns.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{Cell, Column, FormatPrecision, Table, TextAlign};
+///
+/// let table = Table::new()
+///     .separator(" | ")
+///     .header_rule(Some('-'))
+///     .column(Column::new("Sensor"))
+///     .column(Column::new("Value").align(TextAlign::Right).precision(FormatPrecision::Max(2)))
+///     .row([Cell::from("temperature"), Cell::from(21.456)])
+///     .row([Cell::from("pressure"), Cell::from(1013)]);
+/// assert_eq!(
+///     table.to_string(),
+///     "Sensor      |   Value\n\
+///      ---------------------\n\
+///      temperature |   21.46\n\
+///      pressure    | 1013   "
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<Cell>>,
+    separator: String,
+    header_rule: Option<char>,
+}
+
+impl Table {
+    /// Creates an empty table with a space as column separator and without a rule
+    /// below the header.
+    pub fn new() -> Self {
+        Self {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            separator: " ".to_string(),
+            header_rule: None,
+        }
+    }
+
+    /// Adds the configuration of the next column. Columns without a configuration
+    /// use [`Column::default`].
+    pub fn column(mut self, column: Column) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Adds a row of cells.
+    pub fn row<C: Into<Cell>>(mut self, cells: impl IntoIterator<Item = C>) -> Self {
+        self.push_row(cells);
+        self
+    }
+
+    /// Adds a row of cells, like [`Self::row`].
+    pub fn push_row<C: Into<Cell>>(&mut self, cells: impl IntoIterator<Item = C>) {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+    }
+
+    /// Sets the string between two columns. Default is `" "`.
+    pub fn separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Sets the character of the rule below the header, e.g. `'-'`. The rule is only
+    /// rendered if a column has a header. Default is `None`.
+    pub const fn header_rule(mut self, header_rule: Option<char>) -> Self {
+        self.header_rule = header_rule;
+        self
+    }
+
+    /// Renders the table into lines, starting with the header if a column has one.
+    pub fn render(&self) -> Vec<String> {
+        let columns = self.render_columns(&Style::PLAIN);
+        let has_header = columns.iter().any(|column| column.has_header);
+
+        let mut lines = Vec::new();
+        if has_header {
+            lines.push(self.join(columns.iter().map(|column| &column.header)));
+            if let Some(rule) = self.header_rule {
+                let width = display_width(&lines[0]);
+                lines.push(core::iter::repeat(rule).take(width).collect());
+            }
+        }
+        for row in 0..self.rows.len() {
+            lines.push(self.join(columns.iter().map(|column| &column.cells[row])));
+        }
+        lines
+    }
+
+    /// Renders the table as a GitHub-flavored Markdown table, i.e. with a header row,
+    /// a delimiter row with alignment markers and a row per table row. Columns with
+    /// numbers are right-aligned (`---:`), all others according to their
+    /// [`TextAlign`]. Pipes in text cells and headers are escaped.
+    ///
+    /// The cells are padded so that the decimal points are also aligned in the
+    /// Markdown source.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use fraction_list_fmt_align::{Cell, Column, Table};
+    ///
+    /// let table = Table::new()
+    ///     .column(Column::new("Benchmark"))
+    ///     .column(Column::new("ms"))
+    ///     .row([Cell::from("parse | fast"), Cell::from(0.25)])
+    ///     .row([Cell::from("render"), Cell::from(12.5)]);
+    /// assert_eq!(
+    ///     table.render_markdown(),
+    ///     [
+    ///         "| Benchmark     | ms    |",
+    ///         "| :------------ | ----: |",
+    ///         "| parse \\| fast |  0.25 |",
+    ///         "| render        | 12.5  |",
+    ///     ]
+    /// );
+    /// ```
+    pub fn render_markdown(&self) -> Vec<String> {
+        let columns = self.render_columns(&Style::MARKDOWN);
+        let row = |cells: Vec<&str>| format!("| {} |", cells.join(" | "));
+
+        let mut lines = Vec::new();
+        lines.push(row(columns.iter().map(|c| c.header.as_str()).collect()));
+        let markers = columns
+            .iter()
+            .map(|column| {
+                let (left, right) = match column.align {
+                    _ if column.has_numbers => ("", ":"),
+                    TextAlign::Left => (":", ""),
+                    TextAlign::Right => ("", ":"),
+                    TextAlign::Center => (":", ":"),
+                };
+                let dashes = column.width - left.len() - right.len();
+                format!("{}{}{}", left, "-".repeat(dashes), right)
+            })
+            .collect::<Vec<_>>();
+        lines.push(row(markers.iter().map(String::as_str).collect()));
+        for index in 0..self.rows.len() {
+            lines.push(row(columns
+                .iter()
+                .map(|column| column.cells[index].as_str())
+                .collect()));
+        }
+        lines
+    }
+
+    /// Renders the header and the cells of every column with the width of the column.
+    fn render_columns(&self, style: &Style) -> Vec<RenderedColumn> {
+        let column_count = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+            .max(self.columns.len());
+        let default_column = Column::default();
+        (0..column_count)
+            .map(|index| {
+                let column = self.columns.get(index).unwrap_or(&default_column);
+                let cells = self
+                    .rows
+                    .iter()
+                    .map(|row| row.get(index).unwrap_or(&Cell::Empty))
+                    .collect::<Vec<_>>();
+                render_column(column, &cells, style)
+            })
+            .collect()
+    }
+
+    fn join<'a>(&self, cells: impl Iterator<Item = &'a String>) -> String {
+        let mut line = String::new();
+        for (index, cell) in cells.enumerate() {
+            if index > 0 {
+                line.push_str(&self.separator);
+            }
+            line.push_str(cell);
+        }
+        line
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, line) in self.render().iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Differences between the output formats of a [`Table`].
+struct Style {
+    /// Escapes text cells and headers.
+    escape: fn(&str) -> String,
+    /// Minimum width of a column.
+    min_width: usize,
+}
+
+impl Style {
+    const PLAIN: Self = Self {
+        escape: str::to_string,
+        min_width: 0,
+    };
+    /// The delimiter row of Markdown tables requires at least three dashes and up to
+    /// two colons.
+    const MARKDOWN: Self = Self {
+        escape: escape_markdown,
+        min_width: 5,
+    };
+}
+
+/// The header and the cells of a column, padded to the width of the column.
+#[derive(Debug)]
+struct RenderedColumn {
+    has_header: bool,
+    has_numbers: bool,
+    align: TextAlign,
+    width: usize,
+    header: String,
+    cells: Vec<String>,
+}
+
+fn render_column(column: &Column, cells: &[&Cell], style: &Style) -> RenderedColumn {
+    let header = (style.escape)(&column.header);
+    let texts = cells
+        .iter()
+        .map(|cell| match cell {
+            Cell::Text(text) => (style.escape)(text),
+            _ => String::new(),
+        })
+        .collect::<Vec<_>>();
+    let numbers = cells
+        .iter()
+        .filter_map(|cell| match cell {
+            Cell::Number(number) => Some(*number),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let aligned_numbers = fmt_align_numbers_with(&numbers, column.precision, &column.options);
+    let numbers_width = aligned_numbers
+        .iter()
+        .map(|number| display_width(number))
+        .max()
+        .unwrap_or(0);
+
+    let text_width = texts
+        .iter()
+        .map(|text| display_width(text))
+        .max()
+        .unwrap_or(0);
+    let width = numbers_width
+        .max(text_width)
+        .max(display_width(&header))
+        .max(style.min_width);
+
+    let mut aligned_numbers = aligned_numbers.into_iter();
+    let cells = cells
+        .iter()
+        .zip(texts.iter())
+        .map(|(cell, text)| match cell {
+            Cell::Number(_) => {
+                // all numbers are moved by the same amount to keep them aligned
+                let number = aligned_numbers.next().unwrap_or_default();
+                let right = numbers_width - display_width(&number);
+                pad(&number, width - numbers_width, right)
+            }
+            Cell::Text(_) => pad_text(text, column.align, width),
+            Cell::Empty => pad("", width, 0),
+        })
+        .collect();
+    RenderedColumn {
+        has_header: !column.header.is_empty(),
+        has_numbers: !numbers.is_empty(),
+        align: column.align,
+        width,
+        header: pad_text(&header, column.align, width),
+        cells,
+    }
+}
+
+/// Escapes pipes, which would end a cell of a Markdown table, and replaces line breaks,
+/// which would end the row, with `<br>`.
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\r', "<br>")
+        .replace('\n', "<br>")
+}
+
+fn pad_text(text: &str, align: TextAlign, width: usize) -> String {
+    let padding = width - display_width(text);
+    match align {
+        TextAlign::Left => pad(text, 0, padding),
+        TextAlign::Right => pad(text, padding, 0),
+        TextAlign::Center => pad(text, padding / 2, padding - padding / 2),
+    }
+}
+
+fn pad(text: &str, left: usize, right: usize) -> String {
+    let mut string = String::with_capacity(left + text.len() + right);
+    string.extend(core::iter::repeat(' ').take(left));
+    string.push_str(text);
+    string.extend(core::iter::repeat(' ').take(right));
+    string
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let table = Table::new()
+            .separator(" | ")
+            .header_rule(Some('-'))
+            .column(Column::new("Name"))
+            .column(
+                Column::new("Value")
+                    .align(TextAlign::Right)
+                    .precision(FormatPrecision::Max(2)),
+            )
+            .row([Cell::from("x"), Cell::from(3.1379_f32)])
+            .row([Cell::from("y"), Cell::from(2.7249_f32)])
+            .row([Cell::from("answer"), Cell::from(42)]);
+        assert_eq!(
+            vec![
+                "Name   | Value",
+                "--------------",
+                "x      |  3.14",
+                "y      |  2.72",
+                "answer | 42   ",
+            ],
+            table.render()
+        );
+    }
+
+    #[test]
+    fn test_table_mixed_columns() {
+        let mut table = Table::new().column(
+            Column::new("")
+                .align(TextAlign::Center)
+                .options(AlignOptions::new().grouping_separator(Some(','))),
+        );
+        table.push_row([Cell::from(-1234.5), Cell::from("x")]);
+        table.push_row([Cell::from("n/a")]);
+        table.push_row([Cell::from(7), Cell::Empty, Cell::from("last")]);
+        table.push_row([Cell::from("very long")]);
+        // no header, the column of the numbers is wider than the numbers
+        assert_eq!(
+            vec![
+                " -1,234.5 x     ",
+                "   n/a          ",
+                "      7     last",
+                "very long       ",
+            ],
+            table.render()
+        );
+    }
+
+    #[test]
+    fn test_table_markdown() {
+        let table = Table::new()
+            .column(Column::new("Name").align(TextAlign::Center))
+            .column(Column::new("a|b").align(TextAlign::Right))
+            .column(Column::new("Value"))
+            .row([Cell::from("x"), Cell::from("|"), Cell::from(-1.5)])
+            .row([Cell::from("long name"), Cell::Empty, Cell::from(100)]);
+        assert_eq!(
+            vec![
+                "|   Name    |  a\\|b | Value |",
+                "| :-------: | ----: | ----: |",
+                "|     x     |    \\| |  -1.5 |",
+                "| long name |       | 100   |",
+            ],
+            table.render_markdown()
+        );
+
+        // a header row is required, even if it is empty
+        let table = Table::new().row(vec![1, 22]);
+        assert_eq!(
+            vec![
+                "|       |       |",
+                "| ----: | ----: |",
+                "|     1 |    22 |"
+            ],
+            table.render_markdown()
+        );
+
+        // line breaks would end the row
+        let table = Table::new()
+            .column(Column::new("a\nb"))
+            .row([Cell::from("x\r\ny\rz")]);
+        assert_eq!(
+            vec!["| a<br>b      |", "| :---------- |", "| x<br>y<br>z |"],
+            table.render_markdown()
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! The display width of strings in a terminal, see [`display_width`].
+//!
+//! This is a compact approximation of the East Asian Width property (UAX #11) and of
+//! extended grapheme clusters (UAX #29) that covers the characters that appear in
+//! formatted numbers and units: combining marks, format characters, fullwidth forms,
+//! CJK ideographs, Hangul and emoji including ZWJ sequences and flags. The known gaps
+//! are documented in the crate documentation.
+
+use core::cmp::Ordering;
+
+/// Zero width joiner, joins two emoji into one grapheme cluster.
+const ZWJ: char = '\u{200D}';
+
+/// Characters without width: combining marks, variation selectors and invisible
+/// format characters. Sorted, non-overlapping, inclusive ranges.
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0000, 0x001F),
+    (0x007F, 0x009F),
+    (0x00AD, 0x00AD),
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x05BF, 0x05BF),
+    (0x05C1, 0x05C2),
+    (0x05C4, 0x05C5),
+    (0x05C7, 0x05C7),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x0670, 0x0670),
+    (0x06D6, 0x06DC),
+    (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8),
+    (0x06EA, 0x06ED),
+    (0x0900, 0x0902),
+    (0x093A, 0x093A),
+    (0x093C, 0x093C),
+    (0x0941, 0x0948),
+    (0x094D, 0x094D),
+    (0x0951, 0x0957),
+    (0x0962, 0x0963),
+    (0x0E31, 0x0E31),
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x1160, 0x11FF),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x202A, 0x202E),
+    (0x2060, 0x2064),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xFEFF, 0xFEFF),
+    (0x1F3FB, 0x1F3FF),
+    (0xE0000, 0xE0FFF),
+];
+
+/// Characters that occupy two columns: East Asian wide and fullwidth characters and
+/// emoji with emoji presentation. Sorted, non-overlapping, inclusive ranges.
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0),
+    (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267F, 0x267F),
+    (0x2693, 0x2693),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x26FD, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x274E, 0x274E),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x16FE0, 0x16FE4),
+    (0x17000, 0x18CFF),
+    (0x1B000, 0x1B2FF),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F200, 0x1F202),
+    (0x1F210, 0x1F23B),
+    (0x1F240, 0x1F248),
+    (0x1F250, 0x1F251),
+    (0x1F260, 0x1F265),
+    (0x1F300, 0x1F320),
+    (0x1F32D, 0x1F335),
+    (0x1F337, 0x1F37C),
+    (0x1F37E, 0x1F393),
+    (0x1F3A0, 0x1F3CA),
+    (0x1F3CF, 0x1F3D3),
+    (0x1F3E0, 0x1F3F0),
+    (0x1F3F4, 0x1F3F4),
+    (0x1F3F8, 0x1F3FA),
+    (0x1F400, 0x1F43E),
+    (0x1F440, 0x1F440),
+    (0x1F442, 0x1F4FC),
+    (0x1F4FF, 0x1F53D),
+    (0x1F54B, 0x1F54E),
+    (0x1F550, 0x1F567),
+    (0x1F57A, 0x1F57A),
+    (0x1F595, 0x1F596),
+    (0x1F5A4, 0x1F5A4),
+    (0x1F5FB, 0x1F64F),
+    (0x1F680, 0x1F6C5),
+    (0x1F6CC, 0x1F6CC),
+    (0x1F6D0, 0x1F6D2),
+    (0x1F6D5, 0x1F6D7),
+    (0x1F6DC, 0x1F6DF),
+    (0x1F6EB, 0x1F6EC),
+    (0x1F6F4, 0x1F6FC),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F7F0, 0x1F7F0),
+    (0x1F90C, 0x1F93A),
+    (0x1F93C, 0x1F945),
+    (0x1F947, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+/// Regional indicator symbols. Two of them form a flag.
+const REGIONAL_INDICATORS: (u32, u32) = (0x1F1E6, 0x1F1FF);
+
+/// Returns the number of columns that the string occupies in a terminal with a
+/// monospace font.
+///
+/// This is the width by which this crate aligns and pads, so it can be used to lay
+/// out text around aligned numbers. See the [crate documentation](crate#display-width)
+/// for the known gaps.
+/// * `-1.5` => `4`
+/// * `−1.5` (`U+2212` minus) => `4`
+/// * `１２` (fullwidth digits) => `4`
+/// * `e\u{301}` (`e` with a combining acute accent) => `1`
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::display_width;
+///
+/// assert_eq!(display_width("12.5 µs"), 7);
+/// assert_eq!(display_width("１２ 円"), 7);
+/// ```
+pub fn display_width(string: &str) -> usize {
+    let mut width = 0;
+    let mut previous = None;
+    // whether the previous character is the first regional indicator of a flag
+    let mut open_flag = false;
+    for char in string.chars() {
+        let is_regional_indicator = in_range(char, REGIONAL_INDICATORS);
+        width += if previous == Some(ZWJ) {
+            // the character is joined to the previous grapheme cluster
+            0
+        } else if is_regional_indicator && open_flag {
+            // second half of a flag; the first one has the width of the flag
+            0
+        } else {
+            char_width(char)
+        };
+        open_flag = is_regional_indicator && !open_flag;
+        previous = Some(char);
+    }
+    width
+}
+
+/// Returns the number of columns of a single character outside of a grapheme cluster.
+fn char_width(char: char) -> usize {
+    if in_ranges(char, ZERO_WIDTH) {
+        0
+    } else if in_ranges(char, WIDE) || in_range(char, REGIONAL_INDICATORS) {
+        2
+    } else {
+        1
+    }
+}
+
+fn in_range(char: char, (start, end): (u32, u32)) -> bool {
+    (start..=end).contains(&u32::from(char))
+}
+
+fn in_ranges(char: char, ranges: &[(u32, u32)]) -> bool {
+    let char = u32::from(char);
+    ranges
+        .binary_search_by(|(start, end)| {
+            if *end < char {
+                Ordering::Less
+            } else if *start > char {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(0, display_width(""));
+        assert_eq!(4, display_width("-1.5"));
+        assert_eq!(4, display_width("\u{2212}1.5"));
+        assert_eq!(5, display_width("1\u{2009}000"));
+        assert_eq!(4, display_width("１２"));
+        assert_eq!(4, display_width("3 µF"));
+        assert_eq!(1, display_width("e\u{301}"));
+        assert_eq!(4, display_width("一二"));
+        // woman, ZWJ, laptop
+        assert_eq!(2, display_width("\u{1F469}\u{200D}\u{1F4BB}"));
+        // thumbs up with skin tone
+        assert_eq!(2, display_width("\u{1F44D}\u{1F3FD}"));
+        // two flags
+        assert_eq!(4, display_width("\u{1F1E9}\u{1F1EA}\u{1F1EB}\u{1F1F7}"));
+        assert_eq!(2, display_width("\u{1F1E9}"));
+    }
+
+    #[test]
+    fn test_tables_are_sorted() {
+        for table in [ZERO_WIDTH, WIDE] {
+            for window in table.windows(2) {
+                assert!(window[0].0 <= window[0].1);
+                assert!(window[0].1 < window[1].0);
+            }
+        }
+    }
+}
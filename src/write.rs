@@ -0,0 +1,193 @@
+//! Streaming of aligned lines into [`io::Write`] and [`fmt::Write`] sinks.
+
+use crate::align::AlignmentLayout;
+use crate::parts::FractionParts;
+use crate::AlignOptions;
+#[cfg(feature = "alloc")]
+use crate::{AlignableNumber, FormatPrecision};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Describes how the aligned entries are written as lines by [`write_aligned`] and
+/// the related functions.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{write_aligned_strings_fmt, AlignOptions, LineFormat};
+///
+/// let line_format = LineFormat::new().prefix("| ").suffix(" |").terminator("\r\n");
+/// let mut table = String::new();
+/// write_aligned_strings_fmt(&mut table, &["-42", "0.5"], &AlignOptions::new(), &line_format)
+///     .unwrap();
+/// assert_eq!(table, "| -42   |\r\n|   0.5 |\r\n");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LineFormat<'a> {
+    prefix: &'a str,
+    suffix: &'a str,
+    terminator: &'a str,
+}
+
+impl<'a> LineFormat<'a> {
+    /// Creates the default line format: no prefix, no suffix and `\n` as line
+    /// terminator.
+    pub const fn new() -> Self {
+        Self {
+            prefix: "",
+            suffix: "",
+            terminator: "\n",
+        }
+    }
+
+    /// Sets the string that is written in front of every entry. Default is `""`.
+    pub const fn prefix(mut self, prefix: &'a str) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Sets the string that is written after every entry, in front of the line
+    /// terminator. Default is `""`.
+    pub const fn suffix(mut self, suffix: &'a str) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    /// Sets the string that terminates every line, including the last one, e.g.
+    /// `"\r\n"`. Default is `"\n"`.
+    pub const fn terminator(mut self, terminator: &'a str) -> Self {
+        self.terminator = terminator;
+        self
+    }
+}
+
+impl Default for LineFormat<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+/// Like [`crate::fmt_align_numbers_with`] but writes the aligned entries line by line
+/// into an [`io::Write`], such as a file or stdout, instead of returning a
+/// `Vec<String>`.
+///
+/// Unlike [`crate::fmt_align_numbers_with`], which renders such a number as an empty
+/// entry, this fails if the [`AlignableNumber`] implementation of a number fails.
+///
+/// ## Example
+/// ```rust
+/// use fraction_list_fmt_align::{
+///     write_aligned, AlignOptions, FormatPrecision, FractionNumber, LineFormat,
+/// };
+///
+/// let numbers = [FractionNumber::F64(-42.0), FractionNumber::F64(0.3214)];
+/// let mut out = Vec::new();
+/// write_aligned(
+///     &mut out,
+///     &numbers,
+///     FormatPrecision::Max(4),
+///     &AlignOptions::new(),
+///     &LineFormat::new(),
+/// )
+/// .unwrap();
+/// assert_eq!(out, b"-42     \n  0.3214\n");
+/// ```
+pub fn write_aligned<W: io::Write, T: AlignableNumber>(
+    mut writer: W,
+    numbers: &[T],
+    precision: FormatPrecision,
+    options: &AlignOptions,
+    line_format: &LineFormat,
+) -> io::Result<()> {
+    let layout = AlignmentLayout::try_from_numbers(numbers, precision, options)
+        .map_err(|_| io::Error::new(io::ErrorKind::Other, "formatter error"))?;
+    let entries = numbers
+        .iter()
+        .map(|number| layout.display_number(number, precision));
+    write_lines_io(&mut writer, entries, line_format)
+}
+
+#[cfg(feature = "alloc")]
+/// Like [`write_aligned`] but writes into a [`fmt::Write`], such as a `String` or a
+/// [`fmt::Formatter`].
+pub fn write_aligned_fmt<W: fmt::Write, T: AlignableNumber>(
+    mut writer: W,
+    numbers: &[T],
+    precision: FormatPrecision,
+    options: &AlignOptions,
+    line_format: &LineFormat,
+) -> fmt::Result {
+    let layout = AlignmentLayout::try_from_numbers(numbers, precision, options)?;
+    let entries = numbers
+        .iter()
+        .map(|number| layout.display_number(number, precision));
+    write_lines_fmt(&mut writer, entries, line_format)
+}
+
+#[cfg(feature = "std")]
+/// Like [`crate::fmt_align_fraction_strings_with`] but writes the aligned entries line
+/// by line into an [`io::Write`] instead of returning a `Vec<String>`.
+pub fn write_aligned_strings<W: io::Write, S: AsRef<str>>(
+    mut writer: W,
+    strings: &[S],
+    options: &AlignOptions,
+    line_format: &LineFormat,
+) -> io::Result<()> {
+    let layout = AlignmentLayout::new(strings, options);
+    let entries = string_parts(strings, options).map(|p| layout.display_parts(p));
+    write_lines_io(&mut writer, entries, line_format)
+}
+
+/// Like [`write_aligned_strings`] but writes into a [`fmt::Write`].
+pub fn write_aligned_strings_fmt<W: fmt::Write, S: AsRef<str>>(
+    mut writer: W,
+    strings: &[S],
+    options: &AlignOptions,
+    line_format: &LineFormat,
+) -> fmt::Result {
+    let layout = AlignmentLayout::new(strings, options);
+    let entries = string_parts(strings, options).map(|p| layout.display_parts(p));
+    write_lines_fmt(&mut writer, entries, line_format)
+}
+
+fn string_parts<'a, S: AsRef<str>>(
+    strings: &'a [S],
+    options: &AlignOptions,
+) -> impl Iterator<Item = FractionParts<'a>> {
+    let decimal_separator = options.input_locale.decimal_separator;
+    strings
+        .iter()
+        .map(move |s| FractionParts::parse(s.as_ref(), decimal_separator))
+}
+
+#[cfg(feature = "std")]
+fn write_lines_io<W: io::Write>(
+    writer: &mut W,
+    entries: impl Iterator<Item = impl fmt::Display>,
+    line_format: &LineFormat,
+) -> io::Result<()> {
+    for entry in entries {
+        write!(
+            writer,
+            "{}{}{}{}",
+            line_format.prefix, entry, line_format.suffix, line_format.terminator
+        )?;
+    }
+    Ok(())
+}
+
+fn write_lines_fmt<W: fmt::Write>(
+    writer: &mut W,
+    entries: impl Iterator<Item = impl fmt::Display>,
+    line_format: &LineFormat,
+) -> fmt::Result {
+    for entry in entries {
+        write!(
+            writer,
+            "{}{}{}{}",
+            line_format.prefix, entry, line_format.suffix, line_format.terminator
+        )?;
+    }
+    Ok(())
+}